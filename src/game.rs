@@ -1,4 +1,7 @@
-use std::fmt::{Debug, Display};
+use std::{
+	fmt::{Debug, Display},
+	str::FromStr
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -11,7 +14,11 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 	let mut module = rune::Module::with_crate_item("hitman_commons", ["game"])?;
 
 	module.ty::<GameVersion>()?;
+	module.ty::<FromStrError>()?;
+	module.ty::<FromU8Error>()?;
 	module.ty::<GamePlatform>()?;
+	module.ty::<GameFlags>()?;
+	module.ty::<Language>()?;
 
 	module
 }
@@ -24,6 +31,7 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune_functions(Self::all__meta))]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub enum GameVersion {
 	#[cfg_attr(feature = "rune", rune(constructor))]
@@ -36,6 +44,14 @@ pub enum GameVersion {
 	H3
 }
 
+impl GameVersion {
+	/// Gets every game, in release order.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::all))]
+	pub fn all() -> Vec<GameVersion> {
+		vec![GameVersion::H1, GameVersion::H2, GameVersion::H3]
+	}
+}
+
 impl Display for GameVersion {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
@@ -46,6 +62,77 @@ impl Display for GameVersion {
 	}
 }
 
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+pub enum FromStrError {
+	#[error("unknown game version: {0}")]
+	UnknownGameVersion(String)
+}
+
+/// Accepts the variant name (`H3`), the display name (`HITMAN 3`) or just the number or year (`3`, `2016`), ignoring
+/// case.
+impl FromStr for GameVersion {
+	type Err = FromStrError;
+
+	#[try_fn]
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let normalised = s.trim().trim_end_matches('™').to_ascii_uppercase();
+
+		match normalised.strip_prefix("HITMAN").map(|x| x.trim()) {
+			Some("" | "1" | "2016") => GameVersion::H1,
+			Some("2") => GameVersion::H2,
+			Some("3") => GameVersion::H3,
+
+			Some(_) => return Err(FromStrError::UnknownGameVersion(s.into())),
+
+			None => match normalised.as_str() {
+				"H1" | "1" | "2016" => GameVersion::H1,
+				"H2" | "2" => GameVersion::H2,
+				"H3" | "3" => GameVersion::H3,
+				_ => return Err(FromStrError::UnknownGameVersion(s.into()))
+			}
+		}
+	}
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+pub enum FromU8Error {
+	#[error("unknown game version: {0}")]
+	UnknownGameVersion(u8)
+}
+
+/// Maps 1, 2 and 3 to H1, H2 and H3 respectively.
+impl TryFrom<u8> for GameVersion {
+	type Error = FromU8Error;
+
+	#[try_fn]
+	fn try_from(value: u8) -> Result<Self, Self::Error> {
+		match value {
+			1 => GameVersion::H1,
+			2 => GameVersion::H2,
+			3 => GameVersion::H3,
+			_ => return Err(FromU8Error::UnknownGameVersion(value))
+		}
+	}
+}
+
+impl From<GameVersion> for u8 {
+	fn from(value: GameVersion) -> Self {
+		match value {
+			GameVersion::H1 => 1,
+			GameVersion::H2 => 2,
+			GameVersion::H3 => 3
+		}
+	}
+}
+
 #[cfg(feature = "rpkg-rs")]
 impl From<rpkg_rs::WoaVersion> for GameVersion {
 	fn from(value: rpkg_rs::WoaVersion) -> Self {
@@ -130,6 +217,7 @@ impl From<GameVersion> for tonytools::Version {
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune_functions(Self::all__meta))]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub enum GamePlatform {
 	#[cfg_attr(feature = "rune", rune(constructor))]
@@ -142,7 +230,25 @@ pub enum GamePlatform {
 	GOG,
 
 	#[cfg_attr(feature = "rune", rune(constructor))]
-	Microsoft
+	Microsoft,
+
+	/// The platform couldn't be determined, such as for an install chosen by the user.
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Unknown
+}
+
+impl GamePlatform {
+	/// Gets every platform.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::all))]
+	pub fn all() -> Vec<GamePlatform> {
+		vec![
+			GamePlatform::Steam,
+			GamePlatform::Epic,
+			GamePlatform::GOG,
+			GamePlatform::Microsoft,
+			GamePlatform::Unknown
+		]
+	}
 }
 
 impl Display for GamePlatform {
@@ -151,7 +257,227 @@ impl Display for GamePlatform {
 			GamePlatform::Steam => write!(f, "Steam"),
 			GamePlatform::Epic => write!(f, "Epic Games"),
 			GamePlatform::GOG => write!(f, "GOG"),
-			GamePlatform::Microsoft => write!(f, "Microsoft")
+			GamePlatform::Microsoft => write!(f, "Microsoft"),
+			GamePlatform::Unknown => write!(f, "Unknown")
+		}
+	}
+}
+
+/// A set of games, as used by the hash list to mark which games an entry applies to.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(
+	feature = "rune",
+	rune_functions(
+		Self::from_bits__meta,
+		Self::bits__meta,
+		Self::h1__meta,
+		Self::h2__meta,
+		Self::h3__meta,
+		Self::contains__meta,
+		Self::insert__meta
+	)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct GameFlags(u8);
+
+#[cfg(feature = "specta")]
+impl specta::Type for GameFlags {
+	fn inline(_: &mut specta::TypeMap, _: &[specta::DataType]) -> specta::DataType {
+		specta::DataType::Primitive(specta::PrimitiveType::u8)
+	}
+}
+
+impl GameFlags {
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_bits))]
+	pub fn from_bits(bits: u8) -> Self {
+		Self(bits)
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn bits(&self) -> u8 {
+		self.0
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn h1(&self) -> bool {
+		self.contains(GameVersion::H1)
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn h2(&self) -> bool {
+		self.contains(GameVersion::H2)
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn h3(&self) -> bool {
+		self.contains(GameVersion::H3)
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn contains(&self, version: GameVersion) -> bool {
+		self.0 & Self::bit(version) != 0
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn insert(&mut self, version: GameVersion) {
+		self.0 |= Self::bit(version);
+	}
+
+	fn bit(version: GameVersion) -> u8 {
+		match version {
+			GameVersion::H1 => 0b001,
+			GameVersion::H2 => 0b010,
+			GameVersion::H3 => 0b100
+		}
+	}
+}
+
+impl FromIterator<GameVersion> for GameFlags {
+	fn from_iter<T: IntoIterator<Item = GameVersion>>(iter: T) -> Self {
+		let mut flags = Self::default();
+
+		for version in iter {
+			flags.insert(version);
+		}
+
+		flags
+	}
+}
+
+/// A language supported by the games, in the order used by a reference's language code.
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(
+	feature = "rune",
+	rune_functions(Self::from_bit__meta, Self::bit__meta, Self::code__meta, Self::from_code__meta)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Language {
+	/// Language-independent (`xx`).
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Neutral,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	English,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	French,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Italian,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	German,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Spanish,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Russian,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	MexicanSpanish,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	BrazilianPortuguese,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Polish,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	SimplifiedChinese,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Japanese,
+
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	TraditionalChinese
+}
+
+impl Language {
+	/// Every language, in order.
+	pub const ALL: [Language; 13] = [
+		Language::Neutral,
+		Language::English,
+		Language::French,
+		Language::Italian,
+		Language::German,
+		Language::Spanish,
+		Language::Russian,
+		Language::MexicanSpanish,
+		Language::BrazilianPortuguese,
+		Language::Polish,
+		Language::SimplifiedChinese,
+		Language::Japanese,
+		Language::TraditionalChinese
+	];
+
+	/// Gets the language with the given language code, if there is one.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_bit))]
+	pub fn from_bit(bit: u8) -> Option<Self> {
+		Self::ALL.get(bit as usize).copied()
+	}
+
+	/// Gets the language code of the language.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn bit(&self) -> u8 {
+		*self as u8
+	}
+
+	/// Gets the short code the games use for the language, such as `en`.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn code(&self) -> &'static str {
+		match self {
+			Language::Neutral => "xx",
+			Language::English => "en",
+			Language::French => "fr",
+			Language::Italian => "it",
+			Language::German => "de",
+			Language::Spanish => "es",
+			Language::Russian => "ru",
+			Language::MexicanSpanish => "mx",
+			Language::BrazilianPortuguese => "br",
+			Language::Polish => "pl",
+			Language::SimplifiedChinese => "cn",
+			Language::Japanese => "jp",
+			Language::TraditionalChinese => "tc"
+		}
+	}
+
+	/// Gets the language with the given short code, such as `en`.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_code))]
+	pub fn from_code(code: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|language| language.code() == code)
+	}
+}
+
+impl Display for Language {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Language::Neutral => write!(f, "Neutral"),
+			Language::English => write!(f, "English"),
+			Language::French => write!(f, "French"),
+			Language::Italian => write!(f, "Italian"),
+			Language::German => write!(f, "German"),
+			Language::Spanish => write!(f, "Spanish"),
+			Language::Russian => write!(f, "Russian"),
+			Language::MexicanSpanish => write!(f, "Mexican Spanish"),
+			Language::BrazilianPortuguese => write!(f, "Brazilian Portuguese"),
+			Language::Polish => write!(f, "Polish"),
+			Language::SimplifiedChinese => write!(f, "Simplified Chinese"),
+			Language::Japanese => write!(f, "Japanese"),
+			Language::TraditionalChinese => write!(f, "Traditional Chinese")
 		}
 	}
 }
@@ -1,4 +1,7 @@
-use crate::game::{GamePlatform, GameVersion};
+use crate::{
+	game::{GamePlatform, GameVersion, Language},
+	metadata::serialize_display
+};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 use thiserror::Error;
@@ -8,35 +11,58 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 	let mut module = rune::Module::with_crate_item("hitman_commons", ["game_detection"])?;
 
 	module.function_meta(detect_installs__meta)?;
+	module.function_meta(detect_install_for__meta)?;
+	module.function_meta(detect_installs_with_warnings__meta)?;
 	module.ty::<GameInstall>()?;
+	module.ty::<GameEdition>()?;
+	module.ty::<DetectionOptions>()?;
 	module.ty::<GameDetectionError>()?;
 
 	Ok(module)
 }
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Serialize)]
+#[serde(tag = "type", content = "data")]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game_detection))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
 pub enum GameDetectionError {
 	#[error("Couldn't get environment variable {0}: {1}")]
-	EnvVar(String, std::env::VarError),
+	EnvVar(String, #[serde(serialize_with = "serialize_display")] std::env::VarError),
 
 	#[error("IO error for {0}: {1}")]
-	Io(String, std::io::Error),
+	Io(String, #[serde(serialize_with = "serialize_display")] std::io::Error),
+
+	#[error("Couldn't resolve Microsoft install location {0}: {1}")]
+	MicrosoftInstallLocation(String, #[serde(serialize_with = "serialize_display")] std::io::Error),
 
 	#[error("JSON deserialisation error for {0}: {1}")]
-	JsonDeserialisation(String, serde_json::Error),
+	JsonDeserialisation(String, #[serde(serialize_with = "serialize_display")] serde_json::Error),
 
 	#[error("VDF deserialisation error for {0}: {1}")]
-	VdfDeserialisation(String, Box<keyvalues_serde::Error>),
+	VdfDeserialisation(String, #[serde(serialize_with = "serialize_display")] Box<keyvalues_serde::Error>),
 
 	#[error("Missing field {0}")]
 	MissingField(String),
 
 	#[error("Value {0} was not type {1}")]
-	IncorrectType(String, String)
+	IncorrectType(String, String),
+
+	#[error("Couldn't recognise the game installed at {0}")]
+	UnrecognisedInstall(String)
+}
+
+/// Options controlling which installs are detected.
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game_detection))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DetectionOptions {
+	/// Whether to skip installs of the H1 free trial and the H3 demo, which may not be full installs of the game.
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub skip_trials: bool
 }
 
 #[derive(Deserialize)]
@@ -45,6 +71,48 @@ struct SteamLibraryFolder {
 	apps: HashMap<String, String>
 }
 
+impl SteamLibraryFolder {
+	/// Gets the edition of a game installed in this library folder from the app IDs of its full release and of its
+	/// trial or demo, preferring the full release if both are installed.
+	fn installed_edition(
+		&self,
+		full_app_id: &str,
+		trial_app_id: &str,
+		trial_edition: GameEdition,
+		skip_trials: bool
+	) -> Option<GameEdition> {
+		if self.apps.contains_key(full_app_id) {
+			Some(GameEdition::Full)
+		} else if !skip_trials && self.apps.contains_key(trial_app_id) {
+			Some(trial_edition)
+		} else {
+			None
+		}
+	}
+}
+
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::game_detection))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum GameEdition {
+	#[default]
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Full,
+
+	/// The H3 demo.
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Demo,
+
+	/// The H1 free trial.
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	FreeTrial
+}
+
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
@@ -61,15 +129,100 @@ pub struct GameInstall {
 	#[cfg_attr(feature = "rune", rune(get, set))]
 	pub platform: GamePlatform,
 
+	/// Whether this is the full game or a trial or demo. Only Steam installs are ever detected as a trial or demo.
+	#[serde(default)]
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub edition: GameEdition,
+
 	pub path: PathBuf
 }
 
+impl GameInstall {
+	/// Gets the path of the game's Runtime folder, which contains its RPKG files.
+	///
+	/// This is a sibling of the Retail folder that `path` points to.
+	pub fn runtime_path(&self) -> PathBuf {
+		let game_folder = self.path.parent().unwrap_or(&self.path);
+
+		["Runtime", "runtime"]
+			.iter()
+			.map(|folder| game_folder.join(folder))
+			.find(|joined_path| joined_path.is_dir())
+			.unwrap_or_else(|| game_folder.join("Runtime"))
+	}
+
+	/// Gets the path of the game's `packagedefinition.txt`, which defines its partitions.
+	pub fn packages_config_path(&self) -> PathBuf {
+		self.runtime_path().join("packagedefinition.txt")
+	}
+
+	/// Whether this is the same game on the same platform as another install, regardless of where each is installed.
+	pub fn same_game(&self, other: &Self) -> bool {
+		self.version == other.version && self.platform == other.platform
+	}
+
+	/// Gets the languages which have localised packages in the game's Runtime folder, such as `chunk0langfr.rpkg`.
+	pub fn installed_languages(&self) -> Result<Vec<Language>, GameDetectionError> {
+		let runtime_path = self.runtime_path();
+
+		let mut found = vec![];
+
+		for entry in std::fs::read_dir(&runtime_path)
+			.map_err(|x| GameDetectionError::Io(runtime_path.to_string_lossy().into(), x))?
+		{
+			let entry = entry.map_err(|x| GameDetectionError::Io(runtime_path.to_string_lossy().into(), x))?;
+
+			if let Some(language) = package_language(&entry.file_name().to_string_lossy()) {
+				found.push(language);
+			}
+		}
+
+		Ok(Language::ALL.into_iter().filter(|x| found.contains(x)).collect())
+	}
+
+	/// Recognises the game installed at a path chosen by the user, without running full detection. The path can be
+	/// either the game folder or its Retail folder.
+	///
+	/// The platform can't be determined from the folder alone, so it is always `GamePlatform::Unknown`, and the install
+	/// is assumed to be the full game.
+	pub fn from_path(path: PathBuf) -> Result<GameInstall, GameDetectionError> {
+		let game_folder = if path.file_name().is_some_and(|x| x.eq_ignore_ascii_case("retail")) {
+			path.parent().map(|x| x.to_owned()).unwrap_or(path)
+		} else {
+			path
+		};
+
+		detection::recognise_install(game_folder.to_owned(), GamePlatform::Unknown, GameEdition::Full)?
+			.ok_or_else(|| GameDetectionError::UnrecognisedInstall(game_folder.to_string_lossy().into()))
+	}
+}
+
+/// Gets the language of a localised package from its file name, like `chunk0langfr.rpkg` or `dlc5lang_fr.rpkg`.
+fn package_language(file_name: &str) -> Option<Language> {
+	let file_name = file_name.to_ascii_lowercase();
+
+	let name = file_name.strip_suffix(".rpkg")?;
+	let name = name.strip_prefix("chunk").or_else(|| name.strip_prefix("dlc"))?;
+	let name = name.trim_start_matches(|x: char| x.is_ascii_digit());
+	let name = name.strip_prefix("lang")?;
+	let name = name.strip_prefix('_').unwrap_or(name);
+
+	let (code, rest) = name.split_at_checked(2)?;
+
+	if !rest.is_empty() && !rest.starts_with("patch") {
+		return None;
+	}
+
+	Language::from_code(code)
+}
+
 #[cfg(feature = "rune")]
 impl GameInstall {
 	fn rune_construct(version: GameVersion, platform: GamePlatform, path: String) -> Self {
 		Self {
 			version,
 			platform,
+			edition: GameEdition::Full,
 			path: PathBuf::from(path)
 		}
 	}
@@ -87,11 +240,64 @@ impl GameInstall {
 	}
 }
 
+/// Detects installed copies of the games, failing if any launcher's data couldn't be read.
 #[cfg_attr(feature = "rune", rune::function(keep))]
 pub fn detect_installs() -> Result<Vec<GameInstall>, GameDetectionError> {
-	detection::detect_installs()
+	detect_installs_with_options(&DetectionOptions::default())
+}
+
+/// Detects installed copies of the games with the given options, failing if any launcher's data couldn't be read.
+pub fn detect_installs_with_options(options: &DetectionOptions) -> Result<Vec<GameInstall>, GameDetectionError> {
+	let (installs, warnings) = detection::detect_installs(options);
+
+	if let Some(error) = warnings
+		.into_iter()
+		.find(|x| !matches!(x, GameDetectionError::UnrecognisedInstall(_)))
+	{
+		return Err(error);
+	}
+
+	Ok(installs)
+}
+
+/// Detects an installed copy of the given game with the given options, failing if any launcher's data couldn't be
+/// read. This runs full detection, so it takes as long as `detect_installs_with_options`.
+///
+/// A full install is preferred over a trial or demo. Beyond that, if the game is installed through more than one
+/// launcher, the launchers are checked in order (Legendary, the Epic Games Launcher, Steam, the Microsoft Store, then
+/// GOG) and the first one with the game wins.
+#[cfg_attr(feature = "rune", rune::function(keep))]
+pub fn detect_install_for(
+	version: GameVersion,
+	options: &DetectionOptions
+) -> Result<Option<GameInstall>, GameDetectionError> {
+	Ok(detect_installs_with_options(options)?
+		.into_iter()
+		.filter(|x| x.version == version)
+		.min_by_key(|x| x.edition != GameEdition::Full))
 }
 
+/// Detects installed copies of the games, returning every install that could be found along with any problems
+/// encountered, such as a launcher whose data couldn't be read, instead of failing outright.
+#[cfg_attr(feature = "rune", rune::function(keep))]
+pub fn detect_installs_with_warnings() -> (Vec<GameInstall>, Vec<GameDetectionError>) {
+	detection::detect_installs(&DetectionOptions::default())
+}
+
+/// Detects installed copies of the games by looking in the default install folders of each launcher on every drive,
+/// without reading the registry or running any processes. This can find fewer installs than `detect_installs`, such as
+/// those in custom Steam library folders.
+pub fn detect_installs_filesystem_only() -> Vec<GameInstall> {
+	detection::detect_installs_filesystem_only()
+}
+
+// H2 was never released on the Epic Games Store, so only H3 and H1 can be found through the Epic launchers
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+const EPIC_H3_APP_NAME: &str = "Eider";
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+const EPIC_H1_APP_NAME: &str = "Barbet";
+
 #[cfg(target_os = "windows")]
 mod detection {
 	use std::collections::HashMap;
@@ -106,10 +312,129 @@ mod detection {
 
 	use crate::game::GameVersion;
 
-	use super::{GameDetectionError, GameInstall, GamePlatform, SteamLibraryFolder};
+	use super::{
+		DetectionOptions, GameDetectionError, GameEdition, GameInstall, GamePlatform, SteamLibraryFolder,
+		EPIC_H1_APP_NAME, EPIC_H3_APP_NAME
+	};
+
+	pub fn detect_installs(options: &DetectionOptions) -> (Vec<GameInstall>, Vec<GameDetectionError>) {
+		let mut check_paths = vec![];
+		let mut warnings = vec![];
+
+		for result in [
+			legendary_paths(),
+			egl_paths(),
+			steam_paths(options.skip_trials),
+			microsoft_paths(),
+			gog_paths()
+		] {
+			match result {
+				Ok(paths) => check_paths.extend(paths),
+				Err(x) => warnings.push(x)
+			}
+		}
+
+		let mut game_installs = vec![];
+
+		for (path, platform, edition) in check_paths {
+			match recognise_install(path, platform, edition) {
+				Ok(Some(install)) => game_installs.push(install),
+				Ok(None) => {}
+				Err(x) => warnings.push(x)
+			}
+		}
+
+		(
+			game_installs
+				.into_iter()
+				.unique_by(|x| x.path.to_owned())
+				.sorted_by_key(|x| x.version)
+				.collect(),
+			warnings
+		)
+	}
+
+	pub fn detect_installs_filesystem_only() -> Vec<GameInstall> {
+		let mut check_paths = vec![];
+
+		for drive in b'A'..=b'Z' {
+			let drive = PathBuf::from(format!(r#"{}:\"#, drive as char));
+
+			for steam_folder in [
+				drive.join("Program Files (x86)").join("Steam"),
+				drive.join("Program Files").join("Steam"),
+				drive.join("SteamLibrary")
+			] {
+				for game_folder in ["HITMAN™", "HITMAN2", "HITMAN 3"] {
+					check_paths.push((
+						steam_folder
+							.join("steamapps")
+							.join("common")
+							.join(game_folder),
+						GamePlatform::Steam,
+						GameEdition::Full
+					));
+				}
+			}
+
+			check_paths.push((
+				drive
+					.join("Program Files")
+					.join("Epic Games")
+					.join("HITMAN3"),
+				GamePlatform::Epic,
+				GameEdition::Full
+			));
+		}
+
+		check_paths.extend(
+			find_microsoft_install_folders()
+				.into_iter()
+				.map(|x| (x, GamePlatform::Microsoft, GameEdition::Full))
+		);
+
+		check_paths
+			.into_iter()
+			.filter_map(|(path, platform, edition)| recognise_install(path, platform, edition).ok().flatten())
+			.unique_by(|x| x.path.to_owned())
+			.sorted_by_key(|x| x.version)
+			.collect()
+	}
+
+	/// Recognises the game installed in a game folder, returning `None` if the folder has no Retail folder.
+	pub fn recognise_install(
+		path: PathBuf,
+		platform: GamePlatform,
+		edition: GameEdition
+	) -> Result<Option<GameInstall>, GameDetectionError> {
+		// Game folder has Retail
+		let subfolder_retail = path.join("Retail").is_dir();
+
+		if !subfolder_retail {
+			return Ok(None);
+		}
+
+		let version = if path.join("Retail").join("HITMAN3.exe").is_file() {
+			GameVersion::H3
+		} else if path.join("Retail").join("HITMAN2.exe").is_file() {
+			GameVersion::H2
+		} else if path.join("Retail").join("HITMAN.exe").is_file() {
+			GameVersion::H1
+		} else {
+			// Partially installed or mid-update; there's no executable to tell which game it is
+			return Err(GameDetectionError::UnrecognisedInstall(path.to_string_lossy().into()));
+		};
+
+		Ok(Some(GameInstall {
+			path: path.join("Retail"),
+			platform,
+			edition,
+			version
+		}))
+	}
 
 	#[try_fn]
-	pub fn detect_installs() -> Result<Vec<GameInstall>, GameDetectionError> {
+	fn legendary_paths() -> Result<Vec<(PathBuf, GamePlatform, GameEdition)>, GameDetectionError> {
 		let legendary_installed_paths = [
 			Path::new(&std::env::var("USERPROFILE").map_err(|x| GameDetectionError::EnvVar("USERPROFILE".into(), x))?)
 				.join(".config")
@@ -136,7 +461,7 @@ mod detection {
 				})?;
 
 				// H3
-				if let Some(data) = legendary_installed_data.get("Eider") {
+				if let Some(data) = legendary_installed_data.get(EPIC_H3_APP_NAME) {
 					check_paths.push((
 						PathBuf::from(
 							data.get("install_path")
@@ -146,12 +471,13 @@ mod detection {
 									GameDetectionError::IncorrectType("install_path".into(), "string".into())
 								})?
 						),
-						GamePlatform::Epic
+						GamePlatform::Epic,
+						GameEdition::Full
 					));
 				}
 
 				// H1
-				if let Some(data) = legendary_installed_data.get("Barbet") {
+				if let Some(data) = legendary_installed_data.get(EPIC_H1_APP_NAME) {
 					check_paths.push((
 						PathBuf::from(
 							data.get("install_path")
@@ -161,12 +487,20 @@ mod detection {
 									GameDetectionError::IncorrectType("install_path".into(), "string".into())
 								})?
 						),
-						GamePlatform::Epic
+						GamePlatform::Epic,
+						GameEdition::Full
 					));
 				}
 			}
 		}
 
+		check_paths
+	}
+
+	#[try_fn]
+	fn egl_paths() -> Result<Vec<(PathBuf, GamePlatform, GameEdition)>, GameDetectionError> {
+		let mut check_paths = vec![];
+
 		// EGL installs
 		if let Ok(hive) = Hive::CurrentUser.open(r#"Software\Epic Games\EOS"#, Security::Read) {
 			match hive.value("ModSdkMetadataDir") {
@@ -187,7 +521,7 @@ mod detection {
 									.as_str()
 									.ok_or_else(|| {
 										GameDetectionError::IncorrectType("AppName".into(), "string".into())
-									})? == "Eider"
+									})? == EPIC_H3_APP_NAME
 								{
 									check_paths.push((
 										PathBuf::from(
@@ -204,7 +538,8 @@ mod detection {
 													)
 												})?
 										),
-										GamePlatform::Epic
+										GamePlatform::Epic,
+										GameEdition::Full
 									));
 								}
 
@@ -215,7 +550,7 @@ mod detection {
 									.as_str()
 									.ok_or_else(|| {
 										GameDetectionError::IncorrectType("AppName".into(), "string".into())
-									})? == "Barbet"
+									})? == EPIC_H1_APP_NAME
 								{
 									check_paths.push((
 										PathBuf::from(
@@ -232,7 +567,8 @@ mod detection {
 													)
 												})?
 										),
-										GamePlatform::Epic
+										GamePlatform::Epic,
+										GameEdition::Full
 									));
 								}
 							}
@@ -249,6 +585,13 @@ mod detection {
 			}
 		}
 
+		check_paths
+	}
+
+	#[try_fn]
+	fn steam_paths(skip_trials: bool) -> Result<Vec<(PathBuf, GamePlatform, GameEdition)>, GameDetectionError> {
+		let mut check_paths = vec![];
+
 		// 	Steam installs
 		if let Ok(hive) = Hive::CurrentUser.open(r#"Software\Valve\Steam"#, Security::Read) {
 			match hive.value("SteamPath") {
@@ -278,13 +621,16 @@ mod detection {
 
 						for folder in folders.values() {
 							// H1, H1 free trial
-							if folder.apps.contains_key("236870") || folder.apps.contains_key("649780") {
+							if let Some(edition) =
+								folder.installed_edition("236870", "649780", GameEdition::FreeTrial, skip_trials)
+							{
 								check_paths.push((
 									PathBuf::from(&folder.path)
 										.join("steamapps")
 										.join("common")
 										.join("HITMAN™"),
-									GamePlatform::Steam
+									GamePlatform::Steam,
+									edition
 								));
 							}
 
@@ -295,18 +641,22 @@ mod detection {
 										.join("steamapps")
 										.join("common")
 										.join("HITMAN2"),
-									GamePlatform::Steam
+									GamePlatform::Steam,
+									GameEdition::Full
 								));
 							}
 
 							// H3, H3 demo
-							if folder.apps.contains_key("1659040") || folder.apps.contains_key("1847520") {
+							if let Some(edition) =
+								folder.installed_edition("1659040", "1847520", GameEdition::Demo, skip_trials)
+							{
 								check_paths.push((
 									PathBuf::from(&folder.path)
 										.join("steamapps")
 										.join("common")
 										.join("HITMAN 3"),
-									GamePlatform::Steam
+									GamePlatform::Steam,
+									edition
 								));
 							}
 						}
@@ -319,64 +669,105 @@ mod detection {
 			}
 		}
 
+		check_paths
+	}
+
+	#[try_fn]
+	fn microsoft_paths() -> Result<Vec<(PathBuf, GamePlatform, GameEdition)>, GameDetectionError> {
+		let mut check_paths = vec![];
+
 		// Microsoft install of H3
-		if let Ok(proc_out) = Command::new("powershell")
+		let install_location = Command::new("powershell")
 			.args(["-Command", "Get-AppxPackage -Name IOInteractiveAS.PC-HITMAN3-BaseGame"])
 			.creation_flags(0x08000000) // CREATE_NO_WINDOW
 			.output()
-		{
-			if let Some(line) = String::from_utf8_lossy(&proc_out.stdout)
-				.lines()
-				.find(|x| x.starts_with("InstallLocation"))
-			{
-				let path = line.split(':').skip(1).collect::<Vec<_>>().join(":");
-
-				check_paths.push((
-					fs::read_link(path.trim()).map_err(|x| GameDetectionError::Io(path.trim().into(), x))?,
-					GamePlatform::Microsoft
-				));
+			.ok()
+			.and_then(|proc_out| {
+				String::from_utf8_lossy(&proc_out.stdout)
+					.lines()
+					.find(|x| x.starts_with("InstallLocation"))
+					.map(|line| PathBuf::from(line.split(':').skip(1).collect::<Vec<_>>().join(":").trim()))
+			});
+
+		if let Some(install_location) = install_location {
+			match fs::read_link(&install_location) {
+				Ok(path) => check_paths.push((path, GamePlatform::Microsoft, GameEdition::Full)),
+
+				// Not every install location is a symlink; some are the game folder itself
+				Err(_) if install_location.join("Retail").is_dir() => {
+					check_paths.push((install_location, GamePlatform::Microsoft, GameEdition::Full));
+				}
+
+				Err(x) => {
+					let known_folders = find_microsoft_install_folders();
+
+					if known_folders.is_empty() {
+						Err(GameDetectionError::MicrosoftInstallLocation(
+							install_location.to_string_lossy().into(),
+							x
+						))?;
+					}
+
+					check_paths.extend(
+						known_folders
+							.into_iter()
+							.map(|x| (x, GamePlatform::Microsoft, GameEdition::Full))
+					);
+				}
 			}
 		}
 
+		check_paths
+	}
+
+	#[try_fn]
+	fn gog_paths() -> Result<Vec<(PathBuf, GamePlatform, GameEdition)>, GameDetectionError> {
+		let mut check_paths = vec![];
+
 		// GOG install of H1
 		if let Ok(hive) = Hive::LocalMachine.open(r#"Software\WOW6432Node\GOG.com\Games\1545448592"#, Security::Read) {
 			match hive.value("path") {
 				Ok(Data::String(d)) => {
-					check_paths.push((PathBuf::from(&d.to_string_lossy()), GamePlatform::GOG));
+					check_paths.push((PathBuf::from(&d.to_string_lossy()), GamePlatform::GOG, GameEdition::Full));
 				}
 
 				_ => Err(GameDetectionError::IncorrectType("path".into(), "string".into()))?
 			}
 		}
 
-		let mut game_installs = vec![];
+		check_paths
+	}
 
-		for (path, platform) in check_paths {
-			// Game folder has Retail
-			let subfolder_retail = path.join("Retail").is_dir();
-
-			if subfolder_retail {
-				game_installs.push(GameInstall {
-					path: path.join("Retail"),
-					platform,
-					version: if path.join("Retail").join("HITMAN3.exe").is_file() {
-						GameVersion::H3
-					} else if path.join("Retail").join("HITMAN2.exe").is_file() {
-						GameVersion::H2
-					} else if path.join("Retail").join("HITMAN.exe").is_file() {
-						GameVersion::H1
-					} else {
-						panic!("Unknown game added to check paths");
-					}
-				});
+	/// Looks for the Microsoft version of H3 in the folders it's known to be installed to, for when the install
+	/// location reported by `Get-AppxPackage` can't be resolved.
+	fn find_microsoft_install_folders() -> Vec<PathBuf> {
+		let mut found = vec![];
+
+		if let Ok(program_files) = std::env::var("ProgramFiles") {
+			if let Ok(entries) = fs::read_dir(Path::new(&program_files).join("WindowsApps")) {
+				found.extend(
+					entries
+						.filter_map(|x| x.ok())
+						.filter(|x| {
+							x.file_name()
+								.to_string_lossy()
+								.starts_with("IOInteractiveAS.PC-HITMAN3-BaseGame_")
+						})
+						.map(|x| x.path())
+				);
 			}
 		}
 
-		game_installs
-			.into_iter()
-			.unique_by(|x| x.path.to_owned())
-			.sorted_unstable_by_key(|x| x.version)
-			.collect()
+		for drive in b'A'..=b'Z' {
+			found.push(PathBuf::from(format!(
+				r#"{}:\XboxGames\HITMAN 3\Content"#,
+				drive as char
+			)));
+		}
+
+		found.retain(|x| x.join("Retail").is_dir());
+
+		found
 	}
 }
 
@@ -391,10 +782,92 @@ mod detection {
 
 	use crate::game::GameVersion;
 
-	use super::{GameDetectionError, GameInstall, GamePlatform, SteamLibraryFolder};
+	use super::{
+		DetectionOptions, GameDetectionError, GameEdition, GameInstall, GamePlatform, SteamLibraryFolder,
+		EPIC_H1_APP_NAME, EPIC_H3_APP_NAME
+	};
+
+	pub fn detect_installs(options: &DetectionOptions) -> (Vec<GameInstall>, Vec<GameDetectionError>) {
+		let mut check_paths = vec![];
+		let mut warnings = vec![];
+
+		for result in [legendary_paths(), steam_paths(options.skip_trials)] {
+			match result {
+				Ok(paths) => check_paths.extend(paths),
+				Err(x) => warnings.push(x)
+			}
+		}
+
+		let mut game_installs = vec![];
+
+		for (path, platform, edition) in check_paths {
+			match recognise_install(path, platform, edition) {
+				Ok(Some(install)) => game_installs.push(install),
+				Ok(None) => {}
+				Err(x) => warnings.push(x)
+			}
+		}
+
+		(
+			game_installs
+				.into_iter()
+				.unique_by(|x| x.path.to_owned())
+				.sorted_by_key(|x| x.version)
+				.collect(),
+			warnings
+		)
+	}
+
+	pub fn detect_installs_filesystem_only() -> Vec<GameInstall> {
+		// Detection on Linux only reads launcher files in the first place
+		detect_installs(&DetectionOptions::default()).0
+	}
+
+	/// Recognises the game installed in a game folder, returning `None` if the folder has no Retail folder and isn't
+	/// the share/data folder of the native Linux H1 port.
+	pub fn recognise_install(
+		path: PathBuf,
+		platform: GamePlatform,
+		edition: GameEdition
+	) -> Result<Option<GameInstall>, GameDetectionError> {
+		// Only H1 has a native Linux port, which keeps its data under share/data without the Windows executable
+		let native_h1 = path.ends_with("share/data");
+
+		let retail_folder = ["Retail", "retail"]
+			.iter()
+			.map(|folder| path.join(folder))
+			.find(|joined_path| joined_path.exists());
+
+		let retail_folder = match retail_folder {
+			Some(retail_folder) => retail_folder,
+			None if native_h1 => path.to_owned(),
+			None => return Ok(None)
+		};
+
+		let version = if retail_folder.join("HITMAN3.exe").is_file() {
+			GameVersion::H3
+		} else if retail_folder.join("HITMAN2.exe").is_file() {
+			GameVersion::H2
+		} else if native_h1
+			|| retail_folder.join("HITMAN.exe").is_file()
+			|| retail_folder.join("hitman.dll").is_file()
+		{
+			GameVersion::H1
+		} else {
+			// Partially installed or mid-update; there's no executable to tell which game it is
+			return Err(GameDetectionError::UnrecognisedInstall(path.to_string_lossy().into()));
+		};
+
+		Ok(Some(GameInstall {
+			path: retail_folder,
+			platform,
+			edition,
+			version
+		}))
+	}
 
 	#[try_fn]
-	pub fn detect_installs() -> Result<Vec<GameInstall>, GameDetectionError> {
+	fn legendary_paths() -> Result<Vec<(PathBuf, GamePlatform, GameEdition)>, GameDetectionError> {
 		let mut check_paths = vec![];
 
 		// Legendary installs
@@ -414,7 +887,7 @@ mod detection {
 				})?;
 
 				// H3
-				if let Some(data) = legendary_installed_data.get("Eider") {
+				if let Some(data) = legendary_installed_data.get(EPIC_H3_APP_NAME) {
 					check_paths.push((
 						PathBuf::from(
 							data.get("install_path")
@@ -424,12 +897,13 @@ mod detection {
 									GameDetectionError::IncorrectType("install_path".into(), "string".into())
 								})?
 						),
-						GamePlatform::Epic
+						GamePlatform::Epic,
+						GameEdition::Full
 					));
 				}
 
 				// H1
-				if let Some(data) = legendary_installed_data.get("Barbet") {
+				if let Some(data) = legendary_installed_data.get(EPIC_H1_APP_NAME) {
 					check_paths.push((
 						PathBuf::from(
 							data.get("install_path")
@@ -439,12 +913,20 @@ mod detection {
 									GameDetectionError::IncorrectType("install_path".into(), "string".into())
 								})?
 						),
-						GamePlatform::Epic
+						GamePlatform::Epic,
+						GameEdition::Full
 					));
 				}
 			}
 		}
 
+		check_paths
+	}
+
+	#[try_fn]
+	fn steam_paths(skip_trials: bool) -> Result<Vec<(PathBuf, GamePlatform, GameEdition)>, GameDetectionError> {
+		let mut check_paths = vec![];
+
 		// Steam installs
 		if let Some(home_dir) = home::home_dir() {
 			let steam_path = match home_dir {
@@ -460,20 +942,23 @@ mod detection {
 					steam_path.join("steamapps").join("libraryfolders.vdf")
 				};
 
-				if let Ok(s) = fs::read_to_string(&steam_path) {
+				if let Ok(s) = fs::read_to_string(&libraryfolders_path) {
 					let folders: HashMap<String, SteamLibraryFolder> = keyvalues_serde::from_str(&s).map_err(|x| {
 						GameDetectionError::VdfDeserialisation(libraryfolders_path.to_string_lossy().into(), x.into())
 					})?;
 
 					for folder in folders.values() {
 						// H1, H1 free trial
-						if folder.apps.contains_key("236870") || folder.apps.contains_key("649780") {
+						if let Some(edition) =
+							folder.installed_edition("236870", "649780", GameEdition::FreeTrial, skip_trials)
+						{
 							check_paths.push((
 								PathBuf::from(&folder.path)
 									.join("steamapps")
 									.join("common")
 									.join("HITMAN™"),
-								GamePlatform::Steam
+								GamePlatform::Steam,
+								edition
 							));
 
 							check_paths.push((
@@ -481,7 +966,8 @@ mod detection {
 									.join("steamapps")
 									.join("common")
 									.join("Hitman™"),
-								GamePlatform::Steam
+								GamePlatform::Steam,
+								edition
 							));
 
 							check_paths.push((
@@ -491,7 +977,8 @@ mod detection {
 									.join("Hitman™")
 									.join("share")
 									.join("data"),
-								GamePlatform::Steam
+								GamePlatform::Steam,
+								edition
 							));
 						}
 
@@ -502,18 +989,22 @@ mod detection {
 									.join("steamapps")
 									.join("common")
 									.join("HITMAN2"),
-								GamePlatform::Steam
+								GamePlatform::Steam,
+								GameEdition::Full
 							));
 						}
 
 						// H3, H3 demo
-						if folder.apps.contains_key("1659040") || folder.apps.contains_key("1847520") {
+						if let Some(edition) =
+							folder.installed_edition("1659040", "1847520", GameEdition::Demo, skip_trials)
+						{
 							check_paths.push((
 								PathBuf::from(&folder.path)
 									.join("steamapps")
 									.join("common")
 									.join("HITMAN 3"),
-								GamePlatform::Steam
+								GamePlatform::Steam,
+								edition
 							));
 						}
 					}
@@ -521,37 +1012,6 @@ mod detection {
 			}
 		}
 
-		let mut game_installs = vec![];
-
-		for (path, platform) in check_paths {
-			let retail_folder = ["Retail", "retail"]
-				.iter()
-				.map(|folder| path.join(folder))
-				.find(|joined_path| joined_path.exists());
-
-			if let Some(retail_folder) = retail_folder {
-				let version = if retail_folder.join("HITMAN3.exe").is_file() {
-					GameVersion::H3
-				} else if retail_folder.join("HITMAN2.exe").is_file() {
-					GameVersion::H2
-				} else if retail_folder.join("HITMAN.exe").is_file() || retail_folder.join("hitman.dll").is_file() {
-					GameVersion::H1
-				} else {
-					panic!("Unknown game added to check paths");
-				};
-
-				game_installs.push(GameInstall {
-					path: retail_folder,
-					platform,
-					version
-				});
-			}
-		}
-
-		game_installs
-			.into_iter()
-			.unique_by(|x| x.path.to_owned())
-			.sorted_unstable_by_key(|x| x.version)
-			.collect()
+		check_paths
 	}
 }
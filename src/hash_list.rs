@@ -1,11 +1,14 @@
-use std::io::Read;
+use std::{borrow::Cow, io::Read};
 
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tryvial::try_fn;
 
-use crate::metadata::{ResourceType, RuntimeID};
+use crate::{
+	game::{GameFlags, GameVersion},
+	metadata::{ResourceType, RuntimeID}
+};
 
 #[cfg(feature = "rune")]
 #[try_fn]
@@ -15,24 +18,25 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 	module.ty::<HashList>()?;
 	module.ty::<HashData>()?;
 	module.ty::<DeserialisationError>()?;
+	module.ty::<SerialisationError>()?;
 
 	module
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct DeserialisedHashList {
+struct DeserialisedHashList<'a> {
 	pub version: u32,
-	pub entries: Vec<DeserialisedEntry>
+	pub entries: Vec<DeserialisedEntry<'a>>
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct DeserialisedEntry {
+struct DeserialisedEntry<'a> {
 	pub resource_type: ResourceType,
 	pub hash: RuntimeID,
-	pub path: String,
-	pub hint: String,
+	pub path: Cow<'a, str>,
+	pub hint: Cow<'a, str>,
 	pub game_flags: u8
 }
 
@@ -46,19 +50,68 @@ struct DeserialisedEntry {
 	rune_functions(
 		Self::from_compressed__meta,
 		Self::to_path__meta,
+		Self::best_name_or_hash__meta,
 		Self::r_get_entry,
+		Self::r_get_path,
 		Self::r_insert_entry,
 		Self::r_remove_entry
 	)
 )]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct HashList {
+	/// The version of the hash list; newer hash lists have higher versions.
 	#[cfg_attr(feature = "rune", rune(get, set))]
 	pub version: u32,
 
 	pub entries: HashMap<RuntimeID, HashData>
 }
 
+impl HashList {
+	fn from_deserialised(hash_list: DeserialisedHashList) -> Self {
+		HashList {
+			version: hash_list.version,
+			entries: hash_list
+				.entries
+				.into_iter()
+				.map(Self::from_deserialised_entry)
+				.collect()
+		}
+	}
+
+	fn from_deserialised_entry(entry: DeserialisedEntry) -> (RuntimeID, HashData) {
+		(
+			entry.hash,
+			HashData {
+				resource_type: entry.resource_type,
+				path: (!entry.path.is_empty()).then(|| entry.path.into_owned()),
+				hint: (!entry.hint.is_empty()).then(|| entry.hint.into_owned()),
+				game_flags: GameFlags::from_bits(entry.game_flags)
+			}
+		)
+	}
+
+	fn to_deserialised(&self) -> DeserialisedHashList {
+		let mut entries = self
+			.entries
+			.iter()
+			.map(|(hash, entry)| DeserialisedEntry {
+				resource_type: entry.resource_type,
+				hash: *hash,
+				path: entry.path.as_deref().unwrap_or_default().into(),
+				hint: entry.hint.as_deref().unwrap_or_default().into(),
+				game_flags: entry.game_flags.bits()
+			})
+			.collect::<Vec<_>>();
+
+		entries.sort_unstable_by_key(|entry| entry.hash);
+
+		DeserialisedHashList {
+			version: self.version,
+			entries
+		}
+	}
+}
+
 #[cfg(feature = "rune")]
 impl HashList {
 	#[rune::function(instance, path = Self::get_entry)]
@@ -66,14 +119,19 @@ impl HashList {
 		self.entries.get(hash).cloned()
 	}
 
+	#[rune::function(instance, path = Self::get_path)]
+	fn r_get_path(&self, hash: &RuntimeID) -> Option<String> {
+		self.get_path(hash).map(|path| path.to_owned())
+	}
+
 	#[rune::function(instance, path = Self::insert_entry)]
 	fn r_insert_entry(&mut self, hash: RuntimeID, data: HashData) {
-		self.entries.insert(hash, data);
+		self.insert(hash, data);
 	}
 
 	#[rune::function(instance, path = Self::remove_entry)]
 	fn r_remove_entry(&mut self, hash: &RuntimeID) -> Option<HashData> {
-		self.entries.remove(hash)
+		self.remove(hash)
 	}
 }
 
@@ -83,12 +141,45 @@ impl HashList {
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::hash_list))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
-#[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune(constructor_fn = Self::rune_construct))]
+#[cfg_attr(feature = "rune", rune_functions(Self::applies_to__meta))]
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct HashData {
 	pub resource_type: ResourceType,
+
+	#[serde(default, deserialize_with = "empty_as_none")]
 	pub path: Option<String>,
-	pub hint: Option<String>
+
+	#[serde(default, deserialize_with = "empty_as_none")]
+	pub hint: Option<String>,
+
+	#[serde(default)]
+	pub game_flags: GameFlags
+}
+
+/// Treats an empty string as a missing value, matching how entries are read from the compressed format.
+fn empty_as_none<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+	Ok(Option::<String>::deserialize(deserializer)?.filter(|x| !x.is_empty()))
+}
+
+impl HashData {
+	/// Whether the entry applies to the given game according to its game flags.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn applies_to(&self, version: GameVersion) -> bool {
+		self.game_flags.contains(version)
+	}
+}
+
+#[cfg(feature = "rune")]
+impl HashData {
+	fn rune_construct(resource_type: ResourceType, path: Option<String>, hint: Option<String>) -> Self {
+		Self {
+			resource_type,
+			path,
+			hint,
+			game_flags: GameFlags::default()
+		}
+	}
 }
 
 #[derive(Error, Debug)]
@@ -101,50 +192,167 @@ pub enum DeserialisationError {
 	DecompressionFailed(#[from] std::io::Error),
 
 	#[error("deserialisation failed: {0}")]
-	DeserialisationFailed(#[from] serde_smile::Error)
+	DeserialisationFailed(#[from] serde_smile::Error),
+
+	#[error("JSON deserialisation failed: {0}")]
+	JsonDeserialisationFailed(#[from] serde_json::Error)
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::hash_list))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+pub enum SerialisationError {
+	#[error("serialisation failed: {0}")]
+	SerialisationFailed(#[from] serde_smile::Error),
+
+	#[error("compression failed: {0}")]
+	CompressionFailed(#[from] std::io::Error)
 }
 
 impl HashList {
 	#[try_fn]
 	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_compressed))]
 	pub fn from_compressed(slice: &[u8]) -> Result<Self, DeserialisationError> {
-		let mut decompressed = vec![];
+		Self::from_deserialised(Self::decompress_and_deserialise(slice)?)
+	}
 
-		brotli_decompressor::Decompressor::new(slice, 4096)
-			.read_to_end(&mut decompressed)
-			.map_err(DeserialisationError::DecompressionFailed)?;
+	/// Loads a hash list like `from_compressed`, building the map of entries in parallel.
+	///
+	/// Only the construction of the map is parallel; decompression and deserialisation still happen on the calling
+	/// thread, and usually take longer.
+	#[cfg(feature = "rayon")]
+	#[try_fn]
+	pub fn from_compressed_parallel(slice: &[u8]) -> Result<Self, DeserialisationError> {
+		use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-		let hash_list: DeserialisedHashList =
-			serde_smile::from_slice(&decompressed).map_err(DeserialisationError::DeserialisationFailed)?;
+		let hash_list = Self::decompress_and_deserialise(slice)?;
 
 		HashList {
 			version: hash_list.version,
 			entries: hash_list
 				.entries
-				.into_iter()
-				.map(|entry| {
-					(
-						entry.hash,
-						HashData {
-							resource_type: entry.resource_type,
-							path: (!entry.path.is_empty()).then_some(entry.path),
-							hint: (!entry.hint.is_empty()).then_some(entry.hint)
-						}
-					)
-				})
+				.into_par_iter()
+				.map(Self::from_deserialised_entry)
 				.collect()
 		}
 	}
 
+	#[try_fn]
+	fn decompress_and_deserialise(slice: &[u8]) -> Result<DeserialisedHashList, DeserialisationError> {
+		let mut decompressed = vec![];
+
+		brotli_decompressor::Decompressor::new(slice, 4096)
+			.read_to_end(&mut decompressed)
+			.map_err(DeserialisationError::DecompressionFailed)?;
+
+		serde_smile::from_slice(&decompressed).map_err(DeserialisationError::DeserialisationFailed)?
+	}
+
+	/// Loads a hash list by streaming the decompressed data into the deserialiser, without buffering it all in memory.
+	#[try_fn]
+	pub fn from_compressed_reader<R: Read>(reader: R) -> Result<Self, DeserialisationError> {
+		let hash_list: DeserialisedHashList =
+			serde_smile::from_reader(brotli_decompressor::Decompressor::new(reader, 4096))
+				.map_err(DeserialisationError::DeserialisationFailed)?;
+
+		Self::from_deserialised(hash_list)
+	}
+
+	/// Loads a hash list from JSON with the same structure as the compressed format, which is easier to write by hand.
+	///
+	/// ```json
+	/// {
+	/// 	"version": 1,
+	/// 	"entries": [{ "resourceType": "TEMP", "hash": "00123456789ABCDE", "path": "", "hint": "", "gameFlags": 7 }]
+	/// }
+	/// ```
+	#[try_fn]
+	pub fn from_json(json: &str) -> Result<Self, DeserialisationError> {
+		let hash_list: DeserialisedHashList =
+			serde_json::from_str(json).map_err(DeserialisationError::JsonDeserialisationFailed)?;
+
+		Self::from_deserialised(hash_list)
+	}
+
+	/// Serialises the hash list to the same compressed format read by `from_compressed`.
+	///
+	/// Entries are written in order of their hash so that the output is reproducible. This uses the highest Brotli
+	/// quality (11) with a window of 22 bits.
+	pub fn to_compressed(&self) -> Result<Vec<u8>, SerialisationError> {
+		self.to_compressed_with(11, 22)
+	}
+
+	/// Serialises the hash list like `to_compressed`, with the given Brotli quality (0 to 11) and window size (in bits,
+	/// 10 to 24).
+	#[try_fn]
+	pub fn to_compressed_with(&self, quality: u32, window: u32) -> Result<Vec<u8>, SerialisationError> {
+		let serialised =
+			serde_smile::to_vec(&self.to_deserialised()).map_err(SerialisationError::SerialisationFailed)?;
+
+		let mut compressed = vec![];
+
+		brotli::BrotliCompress(
+			&mut serialised.as_slice(),
+			&mut compressed,
+			&brotli::enc::BrotliEncoderParams {
+				quality: quality as i32,
+				lgwin: window as i32,
+				..Default::default()
+			}
+		)
+		.map_err(SerialisationError::CompressionFailed)?;
+
+		compressed
+	}
+
+	/// Loads a compressed hash list on top of this one, overriding any existing entries with the same hash.
+	///
+	/// The version becomes the higher of the two versions.
+	#[try_fn]
+	pub fn extend_compressed(&mut self, slice: &[u8]) -> Result<(), DeserialisationError> {
+		let other = Self::from_compressed(slice)?;
+
+		self.version = self.version.max(other.version);
+		self.entries.extend(other.entries);
+	}
+
+	/// Adds or replaces a single entry, returning the previous entry for that hash if there was one.
+	pub fn insert(&mut self, hash: RuntimeID, data: HashData) -> Option<HashData> {
+		self.entries.insert(hash, data)
+	}
+
+	/// Removes a single entry, returning it if it was present.
+	pub fn remove(&mut self, hash: &RuntimeID) -> Option<HashData> {
+		self.entries.remove(hash)
+	}
+
+	/// Gets the path of a resource, if known, without copying it.
+	pub fn get_path(&self, hash: &RuntimeID) -> Option<&str> {
+		self.entries.get(hash).and_then(|entry| entry.path.as_deref())
+	}
+
 	/// Gets the path of a resource if possible; otherwise just returns the hash.
 	#[cfg_attr(feature = "rune", rune::function(keep))]
 	pub fn to_path(&self, hash: &RuntimeID) -> String {
-		if let Some(entry) = self.entries.get(hash) {
-			if let Some(path) = entry.path.as_ref() {
-				return path.to_owned();
-			}
-		}
+		self.get_path(hash)
+			.map(|path| path.to_owned())
+			.unwrap_or_else(|| hash.to_string())
+	}
 
-		hash.to_string()
+	/// Gets the best available name for a resource: its path if known, otherwise its hint.
+	pub fn best_name(&self, hash: &RuntimeID) -> Option<&str> {
+		self.entries
+			.get(hash)
+			.and_then(|entry| entry.path.as_deref().or(entry.hint.as_deref()))
+	}
+
+	/// Gets the best available name for a resource, falling back to the hash if neither a path nor a hint is known.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn best_name_or_hash(&self, hash: &RuntimeID) -> String {
+		self.best_name(hash)
+			.map(|name| name.to_owned())
+			.unwrap_or_else(|| hash.to_string())
 	}
 }
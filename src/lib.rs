@@ -1,5 +1,6 @@
 pub mod game;
 pub mod metadata;
+pub mod prim;
 pub mod rpkg_tool;
 
 #[cfg(feature = "resourcelib")]
@@ -15,6 +16,7 @@ pub mod game_detection;
 pub fn rune_install(ctx: &mut rune::Context, allow_dangerous: bool) -> Result<(), rune::ContextError> {
 	ctx.install(game::rune_module()?)?;
 	ctx.install(metadata::rune_module()?)?;
+	ctx.install(prim::rune_module()?)?;
 	ctx.install(rpkg_tool::rune_module()?)?;
 
 	#[cfg(feature = "resourcelib")]
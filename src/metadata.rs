@@ -1,8 +1,10 @@
 use core::{fmt, str};
 use std::{
+	collections::HashMap,
 	fmt::{Debug, Display},
 	io::{Cursor, Read, Seek, SeekFrom},
-	str::FromStr
+	str::FromStr,
+	sync::OnceLock
 };
 
 #[cfg(feature = "serde")]
@@ -14,7 +16,13 @@ use serde_hex::{SerHex, StrictCap};
 use thiserror::Error;
 use tryvial::try_fn;
 
-use crate::rpkg_tool::{RpkgInteropError, RpkgResourceMeta};
+use crate::{
+	game::{GameVersion, Language},
+	rpkg_tool::{RpkgInteropError, RpkgResourceMeta}
+};
+
+mod dependency_index;
+pub use dependency_index::DependencyIndex;
 
 #[cfg(feature = "rune")]
 #[try_fn]
@@ -27,9 +35,12 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 	module.ty::<ResourceReference>()?;
 	module.ty::<ReferenceFlags>()?;
 	module.ty::<ReferenceType>()?;
+	module.ty::<ReferenceFormat>()?;
 	module.ty::<ResourceTypeError>()?;
 	module.ty::<ResourceMetadata>()?;
+	module.ty::<MetadataDiff>()?;
 	module.ty::<ExtendedResourceMetadata>()?;
+	module.ty::<DependencyIndex>()?;
 	module.ty::<MetadataCalculationError>()?;
 	module.ty::<FromRpkgResourceMetaError>()?;
 
@@ -39,6 +50,10 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 	module
 }
 
+/// The ID of a resource.
+///
+/// Formatting (with `Display` or `Debug`) always gives the 16-character uppercase hex form and never looks up a path;
+/// use a `HashList` to get the path of an ID.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
@@ -50,7 +65,10 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 		Self::from_path__meta,
 		Self::r_from_str,
 		Self::r_from_u64,
-		Self::r_as_u64
+		Self::r_as_u64,
+		Self::is_valid__meta,
+		Self::from_u64_truncating__meta,
+		Self::meta_filename__meta
 	)
 )]
 #[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
@@ -101,7 +119,7 @@ impl TryFrom<u64> for RuntimeID {
 
 	#[try_fn]
 	fn try_from(value: u64) -> Result<Self, Self::Error> {
-		if value < 0x00FFFFFFFFFFFFFF {
+		if Self::is_valid(value) {
 			Self(value)
 		} else {
 			return Err(FromU64Error::TooHigh);
@@ -158,6 +176,7 @@ impl Debug for RuntimeID {
 }
 
 impl RuntimeID {
+	/// Parses either a 16-character hex ID or a resource path, which is hashed with `from_path`.
 	#[try_fn]
 	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_any))]
 	pub fn from_any(val: &str) -> Result<Self, FromStrError> {
@@ -168,9 +187,15 @@ impl RuntimeID {
 		}
 	}
 
+	/// Computes the ID of a resource path. This is a pure computation; the path is not recorded anywhere.
 	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_path))]
 	pub fn from_path(path: &str) -> Self {
-		let digest = md5::compute(path.to_ascii_lowercase());
+		// Paths are usually already lowercase, in which case there's no need to allocate a lowercased copy
+		let digest = if path.bytes().any(|x| x.is_ascii_uppercase()) {
+			md5::compute(path.to_ascii_lowercase())
+		} else {
+			md5::compute(path)
+		};
 
 		let mut val = 0u64;
 		for i in 1..8 {
@@ -183,6 +208,29 @@ impl RuntimeID {
 	pub fn as_u64(&self) -> &u64 {
 		&self.0
 	}
+
+	/// Whether the given value is in the range of valid IDs; that is, whether converting it to an ID would succeed.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::is_valid))]
+	pub fn is_valid(value: u64) -> bool {
+		value < 0x00FFFFFFFFFFFFFF
+	}
+
+	/// Converts a full 64-bit value to an ID by dropping its high byte, rather than failing like `try_from` would if
+	/// the high byte is set.
+	///
+	/// Returns `None` if the low seven bytes are all `0xFF`, as `0x00FFFFFFFFFFFFFF` isn't a valid ID either.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_u64_truncating))]
+	pub fn from_u64_truncating(value: u64) -> Option<Self> {
+		let value = value & 0x00FFFFFFFFFFFFFF;
+		Self::is_valid(value).then_some(Self(value))
+	}
+
+	/// Gets the name RPKG Tool gives the `.meta` file of a resource with this ID and the given type, such as
+	/// `00123456789ABCDE.TEMP.meta`.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn meta_filename(&self, resource_type: ResourceType) -> String {
+		format!("{self}.{resource_type}.meta")
+	}
 }
 
 #[cfg(feature = "rune")]
@@ -224,14 +272,60 @@ impl From<RuntimeID> for rpkg_rs::resource::runtime_resource_id::RuntimeResource
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
-#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(
+	feature = "rune",
+	rune_functions(Self::install__meta, Self::weak__meta, Self::with_type__meta)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord)]
 pub struct ResourceReference {
 	pub resource: RuntimeID,
 	pub flags: ReferenceFlags
 }
 
+impl ResourceReference {
+	/// Creates an install dependency with default flags.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::install))]
+	pub fn install(resource: RuntimeID) -> Self {
+		Self::with_type(resource, ReferenceType::Install)
+	}
+
+	/// Creates a weak reference with default flags.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::weak))]
+	pub fn weak(resource: RuntimeID) -> Self {
+		Self::with_type(resource, ReferenceType::Weak)
+	}
+
+	/// Creates a reference of the given type with otherwise default flags.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::with_type))]
+	pub fn with_type(resource: RuntimeID, reference_type: ReferenceType) -> Self {
+		Self {
+			resource,
+			flags: ReferenceFlags {
+				reference_type,
+				..Default::default()
+			}
+		}
+	}
+}
+
+impl Display for ResourceReference {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} ({:?}", self.resource, self.flags.reference_type)?;
+
+		if self.flags.acquired {
+			write!(f, ", acquired")?;
+		}
+
+		if self.flags.language_code != ReferenceFlags::ALL_LANGUAGES {
+			write!(f, ", lang={}", self.flags.language_code)?;
+		}
+
+		write!(f, ")")
+	}
+}
+
 #[cfg(feature = "schemars")]
 impl schemars::JsonSchema for ResourceReference {
 	fn schema_name() -> String {
@@ -314,13 +408,19 @@ impl<'de> Deserialize<'de> for ResourceReference {
 	rune_functions(
 		Self::r_default,
 		Self::from_any__meta,
+		Self::from_any_with_format__meta,
 		Self::from_legacy__meta,
 		Self::from_modern__meta,
 		Self::as_legacy__meta,
-		Self::as_modern__meta
+		Self::as_modern__meta,
+		Self::as_format__meta,
+		Self::is_all_languages__meta,
+		Self::language__meta,
+		Self::for_language__meta,
+		Self::languages__meta
 	)
 )]
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord)]
 pub struct ReferenceFlags {
 	#[cfg_attr(feature = "serde", serde(default))]
 	#[cfg_attr(feature = "serde", serde(rename = "type"))]
@@ -340,7 +440,13 @@ pub struct ReferenceFlags {
 
 #[cfg(feature = "serde")]
 fn is_default_flags(val: &ReferenceFlags) -> bool {
-	val.reference_type == ReferenceType::Install && !val.acquired && val.language_code == 0b0001_1111
+	val.reference_type == ReferenceType::Install && !val.acquired && val.language_code == ReferenceFlags::ALL_LANGUAGES
+}
+
+/// Serialises a value, such as an error from another crate, as its `Display` form.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_display<T: Display, S: serde::Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.collect_str(value)
 }
 
 #[cfg(feature = "serde")]
@@ -350,12 +456,12 @@ fn is_false(val: &bool) -> bool {
 
 #[cfg(feature = "serde")]
 fn all_ones() -> u8 {
-	0b0001_1111
+	ReferenceFlags::ALL_LANGUAGES
 }
 
 #[cfg(feature = "serde")]
 fn is_all_ones(val: &u8) -> bool {
-	*val == 0b0001_1111
+	*val == ReferenceFlags::ALL_LANGUAGES
 }
 
 impl Default for ReferenceFlags {
@@ -363,12 +469,15 @@ impl Default for ReferenceFlags {
 		Self {
 			reference_type: ReferenceType::Install,
 			acquired: false,
-			language_code: 0b0001_1111
+			language_code: Self::ALL_LANGUAGES
 		}
 	}
 }
 
 impl ReferenceFlags {
+	/// The language code of a reference that applies to every language.
+	pub const ALL_LANGUAGES: u8 = 0b0001_1111;
+
 	#[cfg_attr(feature = "rune", rune::function(path = Self::default))]
 	fn r_default() -> Self {
 		Self::default()
@@ -378,9 +487,15 @@ impl ReferenceFlags {
 impl ReferenceFlags {
 	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_any))]
 	pub fn from_any(flag: u8) -> Self {
+		Self::from_any_with_format(flag).0
+	}
+
+	/// Parse the flags, detecting the format, and return which format was detected.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_any_with_format))]
+	pub fn from_any_with_format(flag: u8) -> (Self, ReferenceFormat) {
 		// First and fourth bits are padding in the legacy format
 		if flag & 0b0000_1001 != 0 {
-			Self::from_modern(flag)
+			(Self::from_modern(flag), ReferenceFormat::Modern)
 		} else {
 			let install_dependency = flag & 0b1000_0000 == 0b1000_0000;
 			let media_streamed = flag & 0b0100_0000 == 0b0100_0000;
@@ -393,13 +508,57 @@ impl ReferenceFlags {
 				|| state_streamed && type_of_streaming_entity
 				|| media_streamed && type_of_streaming_entity
 			{
-				Self::from_modern(flag)
+				(Self::from_modern(flag), ReferenceFormat::Modern)
 			} else {
-				Self::from_legacy(flag)
+				(Self::from_legacy(flag), ReferenceFormat::Legacy)
 			}
 		}
 	}
 
+	/// Convert the flags to the given format.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn as_format(&self, format: ReferenceFormat) -> u8 {
+		match format {
+			ReferenceFormat::Legacy => self.as_legacy(),
+			ReferenceFormat::Modern => self.as_modern()
+		}
+	}
+
+	/// Whether the reference applies to every language, rather than a single one.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn is_all_languages(&self) -> bool {
+		self.language_code == Self::ALL_LANGUAGES
+	}
+
+	/// Gets the single language the reference applies to, if it doesn't apply to every language.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn language(&self) -> Option<Language> {
+		if self.is_all_languages() {
+			None
+		} else {
+			Language::from_bit(self.language_code)
+		}
+	}
+
+	/// Creates default flags for a reference which only applies to the given language.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::for_language))]
+	pub fn for_language(language: Language) -> Self {
+		Self {
+			language_code: language.bit(),
+			..Default::default()
+		}
+	}
+
+	/// Gets the languages the reference applies to.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn languages(&self) -> Vec<Language> {
+		if self.is_all_languages() {
+			Language::ALL.to_vec()
+		} else {
+			Language::from_bit(self.language_code).into_iter().collect()
+		}
+	}
+
 	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_legacy))]
 	pub fn from_legacy(flag: u8) -> Self {
 		let install_dependency = flag & 0b1000_0000 == 0b1000_0000;
@@ -424,7 +583,7 @@ impl ReferenceFlags {
 				ReferenceType::Normal
 			},
 			acquired: runtime_acquired,
-			language_code: 0x1F
+			language_code: Self::ALL_LANGUAGES
 		}
 	}
 
@@ -483,7 +642,7 @@ impl ReferenceFlags {
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default, PartialOrd, Ord)]
 pub enum ReferenceType {
 	#[default]
 	#[cfg_attr(feature = "rune", rune(constructor))]
@@ -505,6 +664,25 @@ pub enum ReferenceType {
 	EntityType // same as Install in modern format
 }
 
+/// The encoding used for reference flags.
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ReferenceFormat {
+	/// The format used by HITMAN 2016 and HITMAN 2.
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Legacy,
+
+	/// The format used by HITMAN 3.
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Modern
+}
+
 /// Core information about a resource.
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
@@ -513,7 +691,14 @@ pub enum ReferenceType {
 #[cfg_attr(feature = "rune", rune(constructor))]
 #[cfg_attr(
 	feature = "rune",
-	rune_functions(Self::infer_scrambled__meta, Self::infer_compressed__meta, Self::to_extended__meta)
+	rune_functions(
+		Self::infer_scrambled__meta,
+		Self::infer_compressed__meta,
+		Self::infer_compressed_for__meta,
+		Self::to_extended__meta,
+		Self::diff__meta,
+		Self::eq_unordered__meta
+	)
 )]
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct ResourceMetadata {
@@ -537,23 +722,135 @@ impl ResourceMetadata {
 
 	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::infer_compressed))]
 	pub fn infer_compressed(resource_type: ResourceType) -> bool {
-		match resource_type.as_ref() {
-			// Always compressed
-			"REPO" | "ASVA" | "VIDB" | "GFXF" | "UICB" | "PRIM" | "WSWB" | "DLGE" | "MATI" | "BMSK" | "TBLU"
-			| "AIBB" | "YSHP" | "MATE" | "MRTN" | "CRMD" | "WSGB" | "LOCR" | "MJBA" | "NAVP" | "BORG" | "ENUM"
-			| "BOXC" | "CPPT" | "ECPB" | "FXAC" | "WBNK" | "ATMD" | "ORES" | "FXAS" | "MRTR" | "RTLV" | "AIBZ"
-			| "GIDX" | "AIRG" | "DITL" | "SDEF" | "CBLU" | "TEMP" | "DSWB" | "GFXI" => true,
+		match known_compression(resource_type) {
+			Some(Compression::Always | Compression::Usually) => true,
+			Some(Compression::Never) => false,
+			Some(Compression::Unspecified) | None => true
+		}
+	}
 
-			// Usually compressed
-			"MATB" | "SCDA" | "JSON" | "ALOC" | "MATT" | "VTXD" | "PREL" | "WWEV" => true,
+	/// Like `infer_compressed`, but accounting for types whose compression differs between games.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::infer_compressed_for))]
+	pub fn infer_compressed_for(resource_type: ResourceType, game_version: GameVersion) -> bool {
+		match (resource_type.as_ref(), game_version) {
+			// HITMAN 2016 never compresses Wwise events
+			("WWEV", GameVersion::H1) => false,
 
-			// Never compressed
-			"LINE" | "WWES" | "WWEM" | "TEXT" | "ERES" | "GFXV" | "TEXD" | "WSGT" | "ASET" | "CLNG" | "ECPT"
-			| "UICT" | "WSWT" | "AIBX" | "ASEB" => false,
+			_ => Self::infer_compressed(resource_type)
+		}
+	}
 
-			_ => true
+	/// Gets the IDs of all resources referenced with the given reference type.
+	pub fn references_of_type(&self, reference_type: ReferenceType) -> impl Iterator<Item = RuntimeID> + '_ {
+		self.references
+			.iter()
+			.filter(move |reference| reference.flags.reference_type == reference_type)
+			.map(|reference| reference.resource)
+	}
+
+	/// Gets the IDs of all resources referenced as install dependencies.
+	pub fn installs(&self) -> impl Iterator<Item = RuntimeID> + '_ {
+		self.references_of_type(ReferenceType::Install)
+	}
+
+	/// Gets the references of the resource.
+	pub fn references(&self) -> &[ResourceReference] {
+		&self.references
+	}
+
+	/// Gets the IDs of all referenced resources, regardless of reference type.
+	pub fn dependencies(&self) -> impl Iterator<Item = RuntimeID> + '_ {
+		self.references.iter().map(|reference| reference.resource)
+	}
+
+	/// Compares this metadata against a newer version of it.
+	///
+	/// References are compared as a multiset, so reordering references is not considered a change; a reference whose
+	/// flags changed appears as both removed and added.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn diff(&self, other: &Self) -> MetadataDiff {
+		fn changed<T: PartialEq>(old: T, new: T) -> Option<(T, T)> {
+			(old != new).then_some((old, new))
+		}
+
+		let mut unmatched: HashMap<&ResourceReference, usize> = HashMap::new();
+
+		for reference in &self.references {
+			*unmatched.entry(reference).or_default() += 1;
+		}
+
+		let mut added_references = vec![];
+
+		for reference in &other.references {
+			match unmatched.get_mut(reference) {
+				Some(count) if *count > 0 => *count -= 1,
+				_ => added_references.push(reference.to_owned())
+			}
+		}
+
+		let mut removed_references = vec![];
+
+		for reference in &self.references {
+			if let Some(count) = unmatched.get_mut(reference).filter(|count| **count > 0) {
+				*count -= 1;
+				removed_references.push(reference.to_owned());
+			}
+		}
+
+		MetadataDiff {
+			id: changed(self.id, other.id),
+			resource_type: changed(self.resource_type, other.resource_type),
+			compressed: changed(self.compressed, other.compressed),
+			scrambled: changed(self.scrambled, other.scrambled),
+			added_references,
+			removed_references
 		}
 	}
+
+	/// Sorts the references by ID and then flags, so that the metadata is serialised the same way regardless of the
+	/// order the references were added in.
+	pub fn sort_references(&mut self) {
+		self.references.sort();
+	}
+
+	/// Whether this metadata is equal to another, ignoring the order of references.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn eq_unordered(&self, other: &Self) -> bool {
+		self.diff(other).is_empty()
+	}
+}
+
+impl<'a> IntoIterator for &'a ResourceMetadata {
+	type Item = &'a ResourceReference;
+	type IntoIter = std::slice::Iter<'a, ResourceReference>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.references.iter()
+	}
+}
+
+/// The differences between two versions of a resource's metadata, as old and new values.
+#[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune_functions(Self::is_empty__meta))]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct MetadataDiff {
+	pub id: Option<(RuntimeID, RuntimeID)>,
+	pub resource_type: Option<(ResourceType, ResourceType)>,
+	pub compressed: Option<(bool, bool)>,
+	pub scrambled: Option<(bool, bool)>,
+	pub added_references: Vec<ResourceReference>,
+	pub removed_references: Vec<ResourceReference>
+}
+
+impl MetadataDiff {
+	/// Whether there are no differences.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn is_empty(&self) -> bool {
+		self == &Self::default()
+	}
 }
 
 #[cfg(feature = "serde")]
@@ -699,6 +996,7 @@ impl<'de> Visitor<'de> for ResMetaVisitor {
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune_functions(Self::into_core__meta))]
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct ExtendedResourceMetadata {
 	#[cfg_attr(feature = "serde", serde(flatten))]
@@ -708,10 +1006,29 @@ pub struct ExtendedResourceMetadata {
 	pub video_memory_requirement: u32
 }
 
+impl ExtendedResourceMetadata {
+	/// Get the core information about the resource.
+	pub fn core(&self) -> &ResourceMetadata {
+		&self.core_info
+	}
+
+	/// Discard the memory requirements, keeping only the core information.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn into_core(self) -> ResourceMetadata {
+		self.core_info
+	}
+}
+
+impl From<ExtendedResourceMetadata> for ResourceMetadata {
+	fn from(value: ExtendedResourceMetadata) -> Self {
+		value.into_core()
+	}
+}
+
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
-#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub struct ResourceType([u8; 4]);
 
 #[cfg(feature = "specta")]
@@ -792,6 +1109,224 @@ impl<'de> Visitor<'de> for ResTypeVisitor {
 	}
 }
 
+/// How resources of a known type are compressed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Compression {
+	Always,
+	Usually,
+	Never,
+
+	/// Known type, but its compression hasn't been catalogued
+	Unspecified
+}
+
+macro_rules! known_resource_types {
+	($($name:ident => $compression:ident),*) => {
+		/// A resource type known to this crate.
+		#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+		pub enum KnownResourceType {
+			$($name),*
+		}
+
+		impl KnownResourceType {
+			/// Every resource type known to this crate.
+			pub const ALL: &'static [KnownResourceType] = &[$(KnownResourceType::$name),*];
+		}
+
+		impl From<KnownResourceType> for ResourceType {
+			fn from(val: KnownResourceType) -> Self {
+				match val {
+					$(KnownResourceType::$name => ResourceType(type_bytes(stringify!($name)))),*
+				}
+			}
+		}
+
+		/// Every resource type known to this crate, along with how it's compressed.
+		const KNOWN_RESOURCE_TYPES: &[(ResourceType, Compression)] = &[
+			$((ResourceType(type_bytes(stringify!($name))), Compression::$compression)),*
+		];
+	};
+}
+
+const fn type_bytes(name: &str) -> [u8; 4] {
+	let bytes = name.as_bytes();
+	[bytes[0], bytes[1], bytes[2], bytes[3]]
+}
+
+known_resource_types! {
+	AIBB => Always,
+	AIBX => Never,
+	AIBZ => Always,
+	AIRG => Always,
+	ALOC => Usually,
+	ASEB => Never,
+	ASET => Never,
+	ASVA => Always,
+	ATMD => Always,
+	BLOB => Unspecified,
+	BMSK => Always,
+	BORG => Always,
+	BOXC => Always,
+	CBLU => Always,
+	CLNG => Never,
+	CPPT => Always,
+	CRMD => Always,
+	DITL => Always,
+	DLGE => Always,
+	DSWB => Always,
+	ECPB => Always,
+	ECPT => Never,
+	ENUM => Always,
+	ERES => Never,
+	FXAC => Always,
+	FXAS => Always,
+	GFXF => Always,
+	GFXI => Always,
+	GFXV => Never,
+	GIDX => Always,
+	HIKC => Unspecified,
+	IMAP => Unspecified,
+	JSON => Usually,
+	LINE => Never,
+	LOCR => Always,
+	MATB => Usually,
+	MATE => Always,
+	MATI => Always,
+	MATT => Usually,
+	MJBA => Always,
+	MRTN => Always,
+	MRTR => Always,
+	NAVP => Always,
+	ORES => Always,
+	PREL => Usually,
+	PRIM => Always,
+	REPO => Always,
+	RTLV => Always,
+	SCDA => Usually,
+	SDEF => Always,
+	SLMX => Unspecified,
+	TBLU => Always,
+	TELI => Unspecified,
+	TEMP => Always,
+	TEXD => Never,
+	TEXT => Never,
+	UICB => Always,
+	UICT => Never,
+	VIDB => Always,
+	VTXD => Usually,
+	WBNK => Always,
+	WSGB => Always,
+	WSGT => Never,
+	WSWB => Always,
+	WSWT => Never,
+	WWEM => Never,
+	WWES => Never,
+	WWEV => Usually,
+	YSHP => Always
+}
+
+impl TryFrom<ResourceType> for KnownResourceType {
+	type Error = ResourceTypeError;
+
+	#[try_fn]
+	fn try_from(val: ResourceType) -> Result<Self, Self::Error> {
+		*KnownResourceType::ALL
+			.iter()
+			.find(|known_type| ResourceType::from(**known_type) == val)
+			.ok_or(ResourceTypeError::Unknown)?
+	}
+}
+
+impl Display for KnownResourceType {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		Display::fmt(&ResourceType::from(*self), f)
+	}
+}
+
+fn known_compression(resource_type: ResourceType) -> Option<Compression> {
+	KNOWN_RESOURCE_TYPES
+		.iter()
+		.find(|(known_type, _)| *known_type == resource_type)
+		.map(|(_, compression)| *compression)
+}
+
+/// Every resource type whose compression is catalogued, as used by `ResourceMetadata::infer_compressed`.
+pub fn known_compressed_types() -> &'static [ResourceType] {
+	static TYPES: OnceLock<Vec<ResourceType>> = OnceLock::new();
+
+	TYPES.get_or_init(|| {
+		KNOWN_RESOURCE_TYPES
+			.iter()
+			.filter(|(_, compression)| *compression != Compression::Unspecified)
+			.map(|(resource_type, _)| *resource_type)
+			.collect()
+	})
+}
+
+/// Known resource types whose system memory requirement can't yet be calculated.
+const UNCATALOGUED_SYSTEM_MEMORY_TYPES: &[&str] = &["FXAC", "HIKC", "IMAP", "SLMX"];
+
+/// Known resource types whose video memory requirement can't yet be calculated.
+const UNCATALOGUED_VIDEO_MEMORY_TYPES: &[&str] = &["BOXC", "HIKC", "IMAP", "SLMX"];
+
+/// Every resource type whose system memory requirement can be calculated by `ResourceMetadata::to_extended`.
+pub fn known_system_memory_types() -> &'static [ResourceType] {
+	static TYPES: OnceLock<Vec<ResourceType>> = OnceLock::new();
+
+	TYPES.get_or_init(|| {
+		KNOWN_RESOURCE_TYPES
+			.iter()
+			.map(|(resource_type, _)| *resource_type)
+			.filter(|resource_type| !UNCATALOGUED_SYSTEM_MEMORY_TYPES.contains(&resource_type.as_ref()))
+			.collect()
+	})
+}
+
+/// Every resource type whose video memory requirement can be calculated by `ResourceMetadata::to_extended`.
+pub fn known_video_memory_types() -> &'static [ResourceType] {
+	static TYPES: OnceLock<Vec<ResourceType>> = OnceLock::new();
+
+	TYPES.get_or_init(|| {
+		KNOWN_RESOURCE_TYPES
+			.iter()
+			.map(|(resource_type, _)| *resource_type)
+			.filter(|resource_type| !UNCATALOGUED_VIDEO_MEMORY_TYPES.contains(&resource_type.as_ref()))
+			.collect()
+	})
+}
+
+impl ResourceType {
+	/// Creates a resource type in a const context, such as
+	/// `const TEMP: ResourceType = ResourceType::new_const(*b"TEMP")`.
+	///
+	/// Panics if any byte isn't an uppercase ASCII letter or digit, which fails compilation in a const context.
+	pub const fn new_const(bytes: [u8; 4]) -> Self {
+		let mut i = 0;
+
+		while i < bytes.len() {
+			assert!(
+				bytes[i].is_ascii_uppercase() || bytes[i].is_ascii_digit(),
+				"resource types must be uppercase ASCII letters and digits"
+			);
+
+			i += 1;
+		}
+
+		Self(bytes)
+	}
+
+	/// Converts the resource type to a string, checking that it's valid UTF-8 rather than assuming it like the `String`
+	/// conversion does. This is only necessary for resource types of uncertain provenance.
+	pub fn to_string_checked(&self) -> Result<String, str::Utf8Error> {
+		str::from_utf8(&self.0).map(|x| x.to_owned())
+	}
+
+	/// Whether this is one of the resource types known to this crate.
+	pub fn is_known(&self) -> bool {
+		known_compression(*self).is_some()
+	}
+}
+
 impl From<ResourceType> for [u8; 4] {
 	fn from(val: ResourceType) -> Self {
 		val.0
@@ -814,7 +1349,10 @@ pub enum ResourceTypeError {
 	InvalidLength,
 
 	#[error("invalid UTF-8: {0}")]
-	InvalidString(#[from] std::string::FromUtf8Error)
+	InvalidString(#[from] std::string::FromUtf8Error),
+
+	#[error("unknown resource type")]
+	Unknown
 }
 
 impl TryFrom<String> for ResourceType {
@@ -862,6 +1400,17 @@ impl TryFrom<[u8; 4]> for ResourceType {
 	}
 }
 
+impl TryFrom<&[u8]> for ResourceType {
+	type Error = ResourceTypeError;
+
+	#[try_fn]
+	fn try_from(val: &[u8]) -> Result<Self, Self::Error> {
+		<[u8; 4]>::try_from(val)
+			.map_err(|_| ResourceTypeError::InvalidLength)?
+			.try_into()?
+	}
+}
+
 impl AsRef<str> for ResourceType {
 	fn as_ref(&self) -> &str {
 		unsafe { str::from_utf8_unchecked(&self.0) }
@@ -899,69 +1448,207 @@ impl PartialEq<String> for ResourceType {
 }
 
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "data"))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
 pub enum MetadataCalculationError {
 	#[error("seek error: {0}")]
-	Seek(#[from] std::io::Error),
+	Seek(#[from] #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))] std::io::Error),
 
 	#[error("unknown resource type {0}")]
-	UnknownResourceType(ResourceType)
+	UnknownResourceType(ResourceType),
+
+	#[error("unknown texture format {0:#X}")]
+	UnknownTextureFormat(u16),
+
+	#[error("calculated size {0} does not fit in 32 bits")]
+	SizeTooLarge(u64),
+
+	#[error("{0} resource is smaller than its header")]
+	InsufficientData(ResourceType),
+
+	#[error("memory requirements of {0} resources can't be calculated yet")]
+	Uncatalogued(ResourceType)
+}
+
+/// A memory requirement calculated from a resource's data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryRequirement {
+	/// The value the game's own metadata would have.
+	Exact(u32),
+
+	/// An approximation, which may not exactly match the value in the game's own metadata.
+	Estimated(u32)
+}
+
+impl MemoryRequirement {
+	pub fn value(self) -> u32 {
+		match self {
+			MemoryRequirement::Exact(x) | MemoryRequirement::Estimated(x) => x
+		}
+	}
+
+	pub fn is_exact(self) -> bool {
+		matches!(self, MemoryRequirement::Exact(_))
+	}
 }
 
 impl ResourceMetadata {
 	#[try_fn]
 	#[cfg_attr(feature = "rune", rune::function(keep))]
 	pub fn to_extended(self, data: &[u8]) -> Result<ExtendedResourceMetadata, MetadataCalculationError> {
+		let (system_memory_requirement, video_memory_requirement) =
+			Self::memory_requirements(self.resource_type, data)?;
+
 		ExtendedResourceMetadata {
-			system_memory_requirement: match self.resource_type.as_ref() {
-				"AIBX" | "AIBZ" | "AIRG" | "ASEB" | "ASET" | "ASVA" | "ATMD" | "BLOB" | "BMSK" | "BORG" | "BOXC"
-				| "CRMD" | "DITL" | "DLGE" | "ECPT" | "ENUM" | "ERES" | "GFXF" | "GFXI" | "GFXV" | "JSON" | "LINE"
-				| "LOCR" | "MATB" | "MATE" | "MATI" | "MATT" | "NAVP" | "ORES" | "PRIM" | "REPO" | "RTLV" | "SDEF"
-				| "TEXD" | "TEXT" | "UICT" | "VIDB" | "VTXD" | "WBNK" | "WSGT" | "WSWT" | "WWEM" | "WWES" | "WWEV"
-				| "TELI" | "CLNG" => 0xFFFFFFFF,
-
-				"AIBB" | "CBLU" | "CPPT" | "DSWB" | "ECPB" | "GIDX" | "TEMP" | "TBLU" | "UICB" | "WSGB" | "WSWB" => {
-					let mut cur = Cursor::new(data);
-					cur.seek(SeekFrom::Start(0x8))?;
-
-					let mut x = [0; 4];
-					cur.read_exact(&mut x)?;
-					u32::from_be_bytes(x)
-				}
+			system_memory_requirement: system_memory_requirement.value(),
+			video_memory_requirement: video_memory_requirement.value(),
+			core_info: self
+		}
+	}
 
-				"ALOC" => ((data.len() as f64) * 1.75) as u32,
+	/// Calculates the system and video memory requirements of a resource of the given type from its data. Values
+	/// which can only be approximated, such as the video memory requirements of textures, are marked as estimated.
+	#[try_fn]
+	pub fn memory_requirements(
+		resource_type: ResourceType,
+		data: &[u8]
+	) -> Result<(MemoryRequirement, MemoryRequirement), MetadataCalculationError> {
+		(
+			Self::system_memory_requirement(resource_type, data)?,
+			Self::video_memory_requirement(resource_type, data)?
+		)
+	}
 
-				"FXAS" | "MJBA" | "MRTN" | "MRTR" | "SCDA" => data.len() as u32,
+	/// Calculates the system memory requirement of a resource of the given type from its data.
+	#[try_fn]
+	pub fn system_memory_requirement(
+		resource_type: ResourceType,
+		data: &[u8]
+	) -> Result<MemoryRequirement, MetadataCalculationError> {
+		MemoryRequirement::Exact(match resource_type.as_ref() {
+			"AIBX" | "AIBZ" | "AIRG" | "ASEB" | "ASET" | "ASVA" | "ATMD" | "BLOB" | "BMSK" | "BORG" | "BOXC"
+			| "CRMD" | "DITL" | "DLGE" | "ECPT" | "ENUM" | "ERES" | "GFXF" | "GFXI" | "GFXV" | "JSON" | "LINE"
+			| "LOCR" | "MATB" | "MATE" | "MATI" | "MATT" | "NAVP" | "ORES" | "PRIM" | "REPO" | "RTLV" | "SDEF"
+			| "TEXD" | "TEXT" | "UICT" | "VIDB" | "VTXD" | "WBNK" | "WSGT" | "WSWT" | "WWEM" | "WWES" | "WWEV"
+			| "TELI" | "CLNG" => 0xFFFFFFFF,
+
+			"AIBB" | "CBLU" | "CPPT" | "DSWB" | "ECPB" | "GIDX" | "TEMP" | "TBLU" | "UICB" | "WSGB" | "WSWB" => {
+				let mut cur = Cursor::new(data);
+				cur.seek(SeekFrom::Start(0x8))?;
+
+				let mut x = [0; 4];
+				cur.read_exact(&mut x)?;
+				u32::from_be_bytes(x)
+			}
 
-				"PREL" => (data.len() - 0x10) as u32,
+			"ALOC" => checked_size(data.len() as u64 * 7 / 4)?,
 
-				"YSHP" => ((data.len() as f64) * 1.5) as u32,
+			"FXAS" | "MJBA" | "MRTN" | "MRTR" | "SCDA" => data.len() as u32,
 
-				"FXAC" | "HIKC" | "IMAP" | "SLMX" => todo!(),
+			"PREL" => checked_size(
+				data.len()
+					.checked_sub(0x10)
+					.ok_or(MetadataCalculationError::InsufficientData(resource_type))? as u64
+			)?,
 
-				_ => return Err(MetadataCalculationError::UnknownResourceType(self.resource_type))
-			},
-			video_memory_requirement: match self.resource_type.as_ref() {
-				"AIBB" | "AIBX" | "AIBZ" | "AIRG" | "ASEB" | "ASET" | "ASVA" | "ATMD" | "BLOB" | "BMSK" | "BORG"
-				| "CBLU" | "CLNG" | "CPPT" | "CRMD" | "DITL" | "DLGE" | "DSWB" | "ECPB" | "ECPT" | "ENUM" | "ERES"
-				| "GFXF" | "GFXI" | "GFXV" | "JSON" | "LINE" | "LOCR" | "MATB" | "MATE" | "MATI" | "MATT" | "GIDX"
-				| "NAVP" | "ORES" | "PRIM" | "REPO" | "RTLV" | "SDEF" | "TBLU" | "TELI" | "TEMP" | "UICB" | "UICT"
-				| "VIDB" | "VTXD" | "WBNK" | "WSGB" | "WSGT" | "WSWB" | "WSWT" | "WWEM" | "WWES" | "WWEV" => 0xFFFFFFFF,
+			"YSHP" => checked_size(data.len() as u64 * 3 / 2)?,
 
-				"ALOC" | "FXAC" | "FXAS" | "MJBA" | "MRTN" | "MRTR" | "PREL" | "SCDA" | "YSHP" => 0,
+			x if UNCATALOGUED_SYSTEM_MEMORY_TYPES.contains(&x) => {
+				return Err(MetadataCalculationError::Uncatalogued(resource_type));
+			}
 
-				"BOXC" | "HIKC" | "IMAP" | "SLMX" | "TEXD" | "TEXT" => todo!(),
+			_ => return Err(MetadataCalculationError::UnknownResourceType(resource_type))
+		})
+	}
 
-				_ => return Err(MetadataCalculationError::UnknownResourceType(self.resource_type))
-			},
-			core_info: self
+	/// Calculates the video memory requirement of a resource of the given type from its data. Requirements which can
+	/// only be approximated, such as those of textures, are marked as estimated, and an error calculating one only
+	/// means that the approximation couldn't be made.
+	#[try_fn]
+	pub fn video_memory_requirement(
+		resource_type: ResourceType,
+		data: &[u8]
+	) -> Result<MemoryRequirement, MetadataCalculationError> {
+		match resource_type.as_ref() {
+			"AIBB" | "AIBX" | "AIBZ" | "AIRG" | "ASEB" | "ASET" | "ASVA" | "ATMD" | "BLOB" | "BMSK" | "BORG"
+			| "CBLU" | "CLNG" | "CPPT" | "CRMD" | "DITL" | "DLGE" | "DSWB" | "ECPB" | "ECPT" | "ENUM" | "ERES"
+			| "GFXF" | "GFXI" | "GFXV" | "JSON" | "LINE" | "LOCR" | "MATB" | "MATE" | "MATI" | "MATT" | "GIDX"
+			| "NAVP" | "ORES" | "PRIM" | "REPO" | "RTLV" | "SDEF" | "TBLU" | "TELI" | "TEMP" | "UICB" | "UICT"
+			| "VIDB" | "VTXD" | "WBNK" | "WSGB" | "WSGT" | "WSWB" | "WSWT" | "WWEM" | "WWES" | "WWEV" => {
+				MemoryRequirement::Exact(0xFFFFFFFF)
+			}
+
+			"ALOC" | "FXAC" | "FXAS" | "MJBA" | "MRTN" | "MRTR" | "PREL" | "SCDA" | "YSHP" => {
+				MemoryRequirement::Exact(0)
+			}
+
+			// TEXD contains the full mip chain in its GPU format, but the game may pad or align it
+			"TEXD" => MemoryRequirement::Estimated(checked_size(data.len() as u64)?),
+
+			"TEXT" => MemoryRequirement::Estimated(estimate_texture_video_memory(data)?),
+
+			x if UNCATALOGUED_VIDEO_MEMORY_TYPES.contains(&x) => {
+				return Err(MetadataCalculationError::Uncatalogued(resource_type));
+			}
+
+			_ => return Err(MetadataCalculationError::UnknownResourceType(resource_type))
 		}
 	}
 }
 
+fn checked_size(size: u64) -> Result<u32, MetadataCalculationError> {
+	u32::try_from(size).map_err(|_| MetadataCalculationError::SizeTooLarge(size))
+}
+
+/// Roughly estimates the video memory required by a texture from the dimensions, format and mips in its TEXT header.
+///
+/// This is an approximation which doesn't account for any padding or alignment the game may apply, so it may not
+/// exactly match the value in the game's own metadata.
+#[try_fn]
+fn estimate_texture_video_memory(data: &[u8]) -> Result<u32, MetadataCalculationError> {
+	let mut cur = Cursor::new(data);
+	cur.seek(SeekFrom::Start(0xC))?;
+
+	let mut x = [0; 2];
+	cur.read_exact(&mut x)?;
+	let width = u64::from(u16::from_le_bytes(x));
+
+	cur.read_exact(&mut x)?;
+	let height = u64::from(u16::from_le_bytes(x));
+
+	cur.read_exact(&mut x)?;
+	let format = u16::from_le_bytes(x);
+
+	let mut mip_count = [0; 1];
+	cur.read_exact(&mut mip_count)?;
+
+	// (block dimension, bytes per block)
+	let (block_dimension, block_size) = match format {
+		0x0A => (1, 8),                       // R16G16B16A16
+		0x1C => (1, 4),                       // R8G8B8A8
+		0x34 => (1, 2),                       // R8G8
+		0x42 => (1, 1),                       // A8
+		0x49 | 0x52 => (4, 8),                // DXT1, BC4
+		0x4C | 0x4F | 0x55 | 0x5A => (4, 16), // DXT3, DXT5, BC5, BC7
+		_ => return Err(MetadataCalculationError::UnknownTextureFormat(format))
+	};
+
+	let size: u64 = (0..u32::from(mip_count[0].max(1)))
+		.map(|mip| {
+			let mip_width = (width >> mip).max(1);
+			let mip_height = (height >> mip).max(1);
+
+			mip_width.div_ceil(block_dimension) * mip_height.div_ceil(block_dimension) * block_size
+		})
+		.sum();
+
+	size.min(u64::from(u32::MAX)) as u32
+}
+
 #[derive(Error, Debug)]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
@@ -981,6 +1668,18 @@ pub enum FromRpkgResourceMetaError {
 	InvalidType(#[from] ResourceTypeError)
 }
 
+fn references_from_rpkg(meta: &RpkgResourceMeta) -> Result<Vec<ResourceReference>, FromRpkgResourceMetaError> {
+	meta.hash_reference_data
+		.iter()
+		.map(|reference| {
+			Ok(ResourceReference {
+				resource: RuntimeID::from_str(&reference.hash)?,
+				flags: ReferenceFlags::from_any(u8::from_str_radix(&reference.flag, 16)?)
+			})
+		})
+		.collect()
+}
+
 impl TryFrom<RpkgResourceMeta> for ResourceMetadata {
 	type Error = FromRpkgResourceMetaError;
 
@@ -994,18 +1693,7 @@ impl TryFrom<RpkgResourceMeta> for ResourceMetadata {
 			resource_type: meta.hash_resource_type.try_into()?,
 			compressed: meta.hash_size & 0x7FFFFFFF != 0,
 			scrambled: meta.hash_size & 0x80000000 == 0x80000000,
-			references: meta
-				.hash_reference_data
-				.into_iter()
-				.map(|x| {
-					Ok(ResourceReference {
-						resource: x.hash.parse().map_err(FromRpkgResourceMetaError::InvalidID)?,
-						flags: ReferenceFlags::from_any(
-							u8::from_str_radix(&x.flag, 16).map_err(FromRpkgResourceMetaError::InvalidFlag)?
-						)
-					})
-				})
-				.collect::<Result<_, Self::Error>>()?
+			references: references_from_rpkg(&meta)?
 		}
 	}
 }
@@ -1024,18 +1712,7 @@ impl TryFrom<RpkgResourceMeta> for ExtendedResourceMetadata {
 				resource_type: meta.hash_resource_type.try_into()?,
 				compressed: meta.hash_size & 0x7FFFFFFF != 0,
 				scrambled: meta.hash_size & 0x80000000 == 0x80000000,
-				references: meta
-					.hash_reference_data
-					.into_iter()
-					.map(|x| {
-						Ok(ResourceReference {
-							resource: x.hash.parse().map_err(FromRpkgResourceMetaError::InvalidID)?,
-							flags: ReferenceFlags::from_any(
-								u8::from_str_radix(&x.flag, 16).map_err(FromRpkgResourceMetaError::InvalidFlag)?
-							)
-						})
-					})
-					.collect::<Result<_, Self::Error>>()?
+				references: references_from_rpkg(&meta)?
 			},
 			system_memory_requirement: meta.hash_size_in_memory,
 			video_memory_requirement: meta.hash_size_in_video_memory
@@ -1078,13 +1755,7 @@ impl TryFrom<&ResourceInfo> for ExtendedResourceMetadata {
 							resource: RuntimeID::from_str(&id.to_hex_string())
 								.map_err(FromResourceInfoError::InvalidID)?,
 							flags: ReferenceFlags {
-								reference_type: match flags.reference_type() {
-									rpkg_rs::resource::resource_package::ReferenceType::INSTALL => {
-										ReferenceType::Install
-									}
-									rpkg_rs::resource::resource_package::ReferenceType::NORMAL => ReferenceType::Normal,
-									rpkg_rs::resource::resource_package::ReferenceType::WEAK => ReferenceType::Weak
-								},
+								reference_type: flags.reference_type().into(),
 								acquired: flags.is_acquired(),
 								language_code: flags.language_code()
 							}
@@ -1116,11 +1787,7 @@ impl TryFrom<&ResourceInfo> for ResourceMetadata {
 					Ok::<_, Self::Error>(ResourceReference {
 						resource: RuntimeID::from_str(&id.to_hex_string()).map_err(FromResourceInfoError::InvalidID)?,
 						flags: ReferenceFlags {
-							reference_type: match flags.reference_type() {
-								rpkg_rs::resource::resource_package::ReferenceType::INSTALL => ReferenceType::Install,
-								rpkg_rs::resource::resource_package::ReferenceType::NORMAL => ReferenceType::Normal,
-								rpkg_rs::resource::resource_package::ReferenceType::WEAK => ReferenceType::Weak
-							},
+							reference_type: flags.reference_type().into(),
 							acquired: flags.is_acquired(),
 							language_code: flags.language_code()
 						}
@@ -1132,3 +1799,123 @@ impl TryFrom<&ResourceInfo> for ResourceMetadata {
 		}
 	}
 }
+
+#[cfg(feature = "rpkg-rs")]
+impl ResourceReference {
+	/// Converts the reference to the form used by rpkg-rs, encoding the flags in the given format.
+	pub fn to_rpkg_rs(
+		&self,
+		format: ReferenceFormat
+	) -> (
+		rpkg_rs::resource::runtime_resource_id::RuntimeResourceID,
+		rpkg_rs::resource::resource_package::ResourceReferenceFlags
+	) {
+		use rpkg_rs::resource::resource_package::{
+			ResourceReferenceFlags, ResourceReferenceFlagsLegacy, ResourceReferenceFlagsStandard
+		};
+
+		(
+			self.resource.into(),
+			match format {
+				ReferenceFormat::Legacy => {
+					ResourceReferenceFlags::Legacy(ResourceReferenceFlagsLegacy::from_bits(self.flags.as_legacy()))
+				}
+
+				ReferenceFormat::Modern => {
+					ResourceReferenceFlags::Standard(ResourceReferenceFlagsStandard::from_bits(self.flags.as_modern()))
+				}
+			}
+		)
+	}
+}
+
+#[cfg(feature = "rpkg-rs")]
+impl ResourceMetadata {
+	/// Converts many `ResourceInfo`s at once, such as a package's entire resource table, stopping at the first error.
+	pub fn from_resource_infos<'a>(
+		infos: impl IntoIterator<Item = &'a ResourceInfo>
+	) -> Result<Vec<ResourceMetadata>, FromResourceInfoError> {
+		let infos = infos.into_iter();
+
+		let mut converted = Vec::with_capacity(infos.size_hint().0);
+
+		for info in infos {
+			converted.push(info.try_into()?);
+		}
+
+		Ok(converted)
+	}
+}
+
+#[cfg(feature = "rpkg-rs")]
+impl From<rpkg_rs::resource::resource_package::ReferenceType> for ReferenceType {
+	fn from(val: rpkg_rs::resource::resource_package::ReferenceType) -> Self {
+		match val {
+			rpkg_rs::resource::resource_package::ReferenceType::INSTALL => ReferenceType::Install,
+			rpkg_rs::resource::resource_package::ReferenceType::NORMAL => ReferenceType::Normal,
+			rpkg_rs::resource::resource_package::ReferenceType::WEAK => ReferenceType::Weak
+		}
+	}
+}
+
+/// rpkg-rs only has the three reference types of the modern format, so the legacy-only types are collapsed into their
+/// modern equivalents: `Media` becomes `WEAK`, `State` becomes `NORMAL` and `EntityType` becomes `INSTALL`.
+#[cfg(feature = "rpkg-rs")]
+impl From<ReferenceType> for rpkg_rs::resource::resource_package::ReferenceType {
+	fn from(val: ReferenceType) -> Self {
+		match val {
+			ReferenceType::Install | ReferenceType::EntityType => {
+				rpkg_rs::resource::resource_package::ReferenceType::INSTALL
+			}
+			ReferenceType::Normal | ReferenceType::State => rpkg_rs::resource::resource_package::ReferenceType::NORMAL,
+			ReferenceType::Weak | ReferenceType::Media => rpkg_rs::resource::resource_package::ReferenceType::WEAK
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn known_memory_types_have_requirements() {
+		let data = [0; 0x100];
+
+		for &resource_type in known_system_memory_types() {
+			assert!(
+				!matches!(
+					ResourceMetadata::system_memory_requirement(resource_type, &data),
+					Err(MetadataCalculationError::UnknownResourceType(_) | MetadataCalculationError::Uncatalogued(_))
+				),
+				"{resource_type} is listed as a known system memory type but has no requirement"
+			);
+		}
+
+		for &resource_type in known_video_memory_types() {
+			assert!(
+				!matches!(
+					ResourceMetadata::video_memory_requirement(resource_type, &data),
+					Err(MetadataCalculationError::UnknownResourceType(_) | MetadataCalculationError::Uncatalogued(_))
+				),
+				"{resource_type} is listed as a known video memory type but has no requirement"
+			);
+		}
+	}
+
+	#[test]
+	fn uncatalogued_memory_types_are_reported() {
+		for resource_type in UNCATALOGUED_SYSTEM_MEMORY_TYPES {
+			assert!(matches!(
+				ResourceMetadata::system_memory_requirement((*resource_type).try_into().unwrap(), &[]),
+				Err(MetadataCalculationError::Uncatalogued(_))
+			));
+		}
+
+		for resource_type in UNCATALOGUED_VIDEO_MEMORY_TYPES {
+			assert!(matches!(
+				ResourceMetadata::video_memory_requirement((*resource_type).try_into().unwrap(), &[]),
+				Err(MetadataCalculationError::Uncatalogued(_))
+			));
+		}
+	}
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use super::{ResourceMetadata, RuntimeID};
+
+/// An index of which resources reference each resource; the inverse of `ResourceMetadata::references`.
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::metadata))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune_functions(Self::r_referrers))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DependencyIndex {
+	referrers: HashMap<RuntimeID, Vec<RuntimeID>>
+}
+
+impl DependencyIndex {
+	/// Gets the resources which reference the given resource, in the order they were added to the index.
+	pub fn referrers(&self, id: &RuntimeID) -> &[RuntimeID] {
+		self.referrers.get(id).map(|x| x.as_slice()).unwrap_or_default()
+	}
+
+	/// Adds the references of a resource to the index.
+	pub fn add(&mut self, metadata: &ResourceMetadata) {
+		for reference in &metadata.references {
+			let referrers = self.referrers.entry(reference.resource).or_default();
+
+			// A resource can reference the same resource more than once
+			if referrers.last() != Some(&metadata.id) {
+				referrers.push(metadata.id);
+			}
+		}
+	}
+}
+
+impl<'a> FromIterator<&'a ResourceMetadata> for DependencyIndex {
+	fn from_iter<T: IntoIterator<Item = &'a ResourceMetadata>>(iter: T) -> Self {
+		let mut index = Self::default();
+
+		for metadata in iter {
+			index.add(metadata);
+		}
+
+		index
+	}
+}
+
+impl FromIterator<ResourceMetadata> for DependencyIndex {
+	fn from_iter<T: IntoIterator<Item = ResourceMetadata>>(iter: T) -> Self {
+		let mut index = Self::default();
+
+		for metadata in iter {
+			index.add(&metadata);
+		}
+
+		index
+	}
+}
+
+#[cfg(feature = "rune")]
+impl DependencyIndex {
+	#[rune::function(instance, path = Self::referrers)]
+	fn r_referrers(&self, id: &RuntimeID) -> Vec<RuntimeID> {
+		self.referrers(id).to_vec()
+	}
+}
@@ -0,0 +1,194 @@
+//! Quick inspection of PRIM (render primitive) resources, without parsing their meshes.
+//!
+//! BORG (bone rig) resources aren't covered; their header has a different layout.
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use thiserror::Error;
+use tryvial::try_fn;
+
+#[cfg(feature = "rune")]
+#[try_fn]
+pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
+	let mut module = rune::Module::with_crate_item("hitman_commons", ["prim"])?;
+
+	module.ty::<PrimHeader>()?;
+	module.ty::<PrimHeaderError>()?;
+
+	module
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::prim))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+pub enum PrimHeaderError {
+	#[error("read error: {0}")]
+	Read(#[from] std::io::Error),
+
+	#[error("expected an object header (type 2) but found type {0}")]
+	NotObjectHeader(u16)
+}
+
+/// The object header of a PRIM resource, which describes the mesh as a whole.
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::prim))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune_functions(Self::from_memory__meta))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrimHeader {
+	/// The offset of the object header within the resource.
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub header_offset: u32,
+
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub draw_destination: u8,
+
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub pack_type: u8,
+
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub prim_type: u16,
+
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub property_flags: u32,
+
+	/// The index of the BORG in the PRIM's references, or `0xFFFFFFFF` if the mesh has no bone rig.
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub bone_rig_resource_index: u32,
+
+	/// The number of sub-meshes.
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub object_count: u32,
+
+	/// The offset of the table of sub-mesh offsets.
+	#[cfg_attr(feature = "rune", rune(get, set))]
+	pub object_table_offset: u32,
+
+	pub bounding_box_min: [f32; 3],
+	pub bounding_box_max: [f32; 3]
+}
+
+impl PrimHeader {
+	/// Reads the object header of a PRIM resource. The first word of the resource is the offset of the header, which
+	/// must have the object header type.
+	#[try_fn]
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::from_memory))]
+	pub fn from_memory(data: &[u8]) -> Result<Self, PrimHeaderError> {
+		let mut cur = Cursor::new(data);
+
+		let mut x = [0; 4];
+		cur.read_exact(&mut x)?;
+		let header_offset = u32::from_le_bytes(x);
+
+		cur.seek(SeekFrom::Start(header_offset.into()))?;
+
+		let mut x = [0; 1];
+		cur.read_exact(&mut x)?;
+		let draw_destination = x[0];
+
+		cur.read_exact(&mut x)?;
+		let pack_type = x[0];
+
+		let mut x = [0; 2];
+		cur.read_exact(&mut x)?;
+		let prim_type = u16::from_le_bytes(x);
+
+		if prim_type != 2 {
+			return Err(PrimHeaderError::NotObjectHeader(prim_type));
+		}
+
+		let mut words = [0; 4];
+
+		for word in &mut words {
+			let mut x = [0; 4];
+			cur.read_exact(&mut x)?;
+			*word = u32::from_le_bytes(x);
+		}
+
+		let [property_flags, bone_rig_resource_index, object_count, object_table_offset] = words;
+
+		let mut bounds = [0.0; 6];
+
+		for bound in &mut bounds {
+			let mut x = [0; 4];
+			cur.read_exact(&mut x)?;
+			*bound = f32::from_le_bytes(x);
+		}
+
+		Self {
+			header_offset,
+			draw_destination,
+			pack_type,
+			prim_type,
+			property_flags,
+			bone_rig_resource_index,
+			object_count,
+			object_table_offset,
+			bounding_box_min: [bounds[0], bounds[1], bounds[2]],
+			bounding_box_max: [bounds[3], bounds[4], bounds[5]]
+		}
+	}
+
+	/// Whether the mesh is weighted to a bone rig.
+	pub fn is_weighted(&self) -> bool {
+		self.property_flags & 0x8 != 0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn prim(prim_type: u16) -> Vec<u8> {
+		let mut data = vec![0; 0x10];
+		data[0..4].copy_from_slice(&0x10u32.to_le_bytes());
+
+		data.extend([0, 1]);
+		data.extend(prim_type.to_le_bytes());
+
+		for word in [0x8u32, 0x3, 0x2, 0x48] {
+			data.extend(word.to_le_bytes());
+		}
+
+		for bound in [-1.0f32, -2.0, -3.0, 1.0, 2.0, 3.0] {
+			data.extend(bound.to_le_bytes());
+		}
+
+		data
+	}
+
+	#[test]
+	fn reads_valid_header() {
+		let header = PrimHeader::from_memory(&prim(2)).unwrap();
+
+		assert_eq!(header.header_offset, 0x10);
+		assert_eq!(header.pack_type, 1);
+		assert_eq!(header.bone_rig_resource_index, 0x3);
+		assert_eq!(header.object_count, 0x2);
+		assert_eq!(header.object_table_offset, 0x48);
+		assert_eq!(header.bounding_box_min, [-1.0, -2.0, -3.0]);
+		assert_eq!(header.bounding_box_max, [1.0, 2.0, 3.0]);
+		assert!(header.is_weighted());
+	}
+
+	#[test]
+	fn rejects_wrong_header_type() {
+		assert!(matches!(
+			PrimHeader::from_memory(&prim(1)),
+			Err(PrimHeaderError::NotObjectHeader(1))
+		));
+	}
+
+	#[test]
+	fn rejects_truncated_data() {
+		let data = prim(2);
+
+		assert!(matches!(
+			PrimHeader::from_memory(&data[..data.len() - 1]),
+			Err(PrimHeaderError::Read(_))
+		));
+		assert!(matches!(PrimHeader::from_memory(&[0x10, 0]), Err(PrimHeaderError::Read(_))));
+	}
+}
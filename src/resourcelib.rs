@@ -1,7 +1,11 @@
+use std::{cmp::Ordering, collections::HashMap};
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use serde_json::Value;
+use thiserror::Error;
+use tryvial::try_fn;
 
 #[cfg(feature = "rune")]
 pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
@@ -27,10 +31,16 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 	module.ty::<BlueprintSubEntityLegacy>()?;
 	module.ty::<EntityBlueprintLegacy>()?;
 	module.ty::<PinConnectionLegacy>()?;
+	module.ty::<EditorOnlySidecar>()?;
+	module.ty::<EntityPairError>()?;
+	module.ty::<SubEntityIndexError>()?;
+	module.ty::<PropertyValueError>()?;
+	module.ty::<EntityFormat>()?;
 
 	Ok(module)
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -52,7 +62,13 @@ pub struct BlueprintSubEntity {
 	pub property_aliases: Vec<PropertyAlias>,
 	pub exposed_entities: Vec<ExposedEntity>,
 	pub exposed_interfaces: Vec<(String, usize)>,
-	pub entity_subsets: Vec<(String, EntitySubset)>
+	pub entity_subsets: Vec<(String, EntitySubset)>,
+
+	/// Any properties not known to this crate, kept so that they survive a round-trip.
+	#[cfg_attr(feature = "serde", serde(flatten))]
+	#[cfg_attr(feature = "specta", specta(skip))]
+	#[cfg_attr(feature = "rune", serde_with(skip_apply))]
+	pub extra: serde_json::Map<String, Value>
 }
 
 #[cfg(feature = "rune")]
@@ -74,13 +90,23 @@ impl BlueprintSubEntity {
 	}
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
-#[cfg_attr(feature = "rune", rune_functions(Self::into_legacy__meta, Self::r_new))]
+#[cfg_attr(
+	feature = "rune",
+	rune_functions(
+		Self::into_legacy__meta,
+		Self::index_of_entity_id__meta,
+		Self::remove_sub_entity__meta,
+		Self::insert_sub_entity__meta,
+		Self::r_new
+	)
+)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct EntityBlueprint {
@@ -107,13 +133,14 @@ impl EntityBlueprint {
 	}
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
-#[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune(constructor_fn = Self::rune_construct))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct FactorySubEntity {
@@ -123,16 +150,84 @@ pub struct FactorySubEntity {
 	pub post_init_property_values: Vec<Property>,
 
 	#[cfg_attr(feature = "serde", serde(default))] // H2 does not have this property
-	pub platform_specific_property_values: Vec<PlatformSpecificProperty>
+	pub platform_specific_property_values: Vec<PlatformSpecificProperty>,
+
+	/// Any properties not known to this crate, kept so that they survive a round-trip.
+	#[cfg_attr(feature = "serde", serde(flatten))]
+	#[cfg_attr(feature = "specta", specta(skip))]
+	#[cfg_attr(feature = "rune", serde_with(skip_apply))]
+	pub extra: serde_json::Map<String, Value>
+}
+
+impl FactorySubEntity {
+	/// Gets the properties which apply on the given platform: the normal properties, with any platform-specific
+	/// properties for that platform replacing those with the same ID or otherwise appended.
+	pub fn properties_for_platform(&self, platform: &str) -> Vec<Property> {
+		self.merge_platform_properties(&self.property_values, platform, false)
+	}
+
+	/// Gets the post-init properties which apply on the given platform, like `properties_for_platform`.
+	pub fn post_init_properties_for_platform(&self, platform: &str) -> Vec<Property> {
+		self.merge_platform_properties(&self.post_init_property_values, platform, true)
+	}
+
+	fn merge_platform_properties(&self, properties: &[Property], platform: &str, post_init: bool) -> Vec<Property> {
+		let mut merged = properties.to_vec();
+
+		for property in self
+			.platform_specific_property_values
+			.iter()
+			.filter(|x| x.platform == platform && x.post_init == post_init)
+		{
+			match merged
+				.iter_mut()
+				.find(|x| x.n_property_id.as_crc32() == property.property_value.n_property_id.as_crc32())
+			{
+				Some(existing) => *existing = property.property_value.to_owned(),
+				None => merged.push(property.property_value.to_owned())
+			}
+		}
+
+		merged
+	}
 }
 
+#[cfg(feature = "rune")]
+impl FactorySubEntity {
+	fn rune_construct(
+		logical_parent: EntityReference,
+		entity_type_resource_index: usize,
+		property_values: Vec<Property>,
+		post_init_property_values: Vec<Property>,
+		platform_specific_property_values: Vec<PlatformSpecificProperty>
+	) -> Self {
+		FactorySubEntity {
+			logical_parent,
+			entity_type_resource_index,
+			property_values,
+			post_init_property_values,
+			platform_specific_property_values,
+			extra: Default::default()
+		}
+	}
+}
+
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
-#[cfg_attr(feature = "rune", rune_functions(Self::into_legacy__meta, Self::r_new))]
+#[cfg_attr(
+	feature = "rune",
+	rune_functions(
+		Self::into_legacy__meta,
+		Self::remove_sub_entity__meta,
+		Self::insert_sub_entity__meta,
+		Self::r_new
+	)
+)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct EntityFactory {
@@ -155,6 +250,7 @@ impl EntityFactory {
 	}
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -162,6 +258,17 @@ impl EntityFactory {
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(
+	feature = "rune",
+	rune_functions(
+		Self::null__meta,
+		Self::local__meta,
+		Self::external__meta,
+		Self::is_null__meta,
+		Self::is_local__meta,
+		Self::is_external__meta
+	)
+)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct EntityReference {
@@ -173,6 +280,73 @@ pub struct EntityReference {
 	pub exposed_entity: String
 }
 
+impl EntityReference {
+	/// A reference to no entity.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::null))]
+	pub fn null() -> Self {
+		Self {
+			entity_id: u64::MAX,
+			external_scene_index: -1,
+			entity_index: -1,
+			exposed_entity: String::new()
+		}
+	}
+
+	/// A reference to the sub-entity at the given index in the same entity.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::local))]
+	pub fn local(entity_index: i32) -> Self {
+		Self {
+			entity_id: u64::MAX,
+			external_scene_index: -1,
+			entity_index,
+			exposed_entity: String::new()
+		}
+	}
+
+	/// A reference to the entity with the given ID in one of the external scenes.
+	#[cfg_attr(feature = "rune", rune::function(keep, path = Self::external))]
+	pub fn external(external_scene_index: i32, entity_id: u64) -> Self {
+		Self {
+			entity_id,
+			external_scene_index,
+			entity_index: -2,
+			exposed_entity: String::new()
+		}
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn is_null(&self) -> bool {
+		self.external_scene_index == -1 && self.entity_index == -1
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn is_local(&self) -> bool {
+		self.external_scene_index == -1 && self.entity_index >= 0
+	}
+
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn is_external(&self) -> bool {
+		self.external_scene_index != -1
+	}
+
+	/// Remaps the index of a local reference, returning `false` if the sub-entity it refers to no longer exists.
+	fn remap_local(&mut self, remap: &dyn Fn(usize) -> Option<usize>) -> bool {
+		if !self.is_local() {
+			return true;
+		}
+
+		match remap(self.entity_index as usize) {
+			Some(index) => {
+				self.entity_index = index as i32;
+				true
+			}
+
+			None => false
+		}
+	}
+}
+
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -188,6 +362,7 @@ pub struct ExposedEntity {
 	pub a_targets: Vec<EntityReference>
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -209,6 +384,14 @@ pub struct PinConnection {
 	pub constant_pin_value: PropertyValue
 }
 
+impl PinConnection {
+	/// Remaps the indices of both ends, returning `false` if either sub-entity no longer exists.
+	fn remap(&mut self, remap: &dyn Fn(usize) -> Option<usize>) -> bool {
+		remap_index(&mut self.from_id, remap) && remap_index(&mut self.to_id, remap)
+	}
+}
+
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -224,6 +407,7 @@ pub struct PlatformSpecificProperty {
 	pub post_init: bool
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -242,6 +426,7 @@ pub struct PropertyAlias {
 	pub s_property_name: String
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -256,6 +441,7 @@ pub struct PropertyOverride {
 	pub property_value: Property
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -269,6 +455,7 @@ pub struct EntitySubset {
 	pub entities: Vec<usize>
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -286,6 +473,7 @@ pub struct ExternalPinConnection {
 	pub constant_pin_value: PropertyValue
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -302,6 +490,7 @@ pub struct Property {
 	pub value: PropertyValue
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
@@ -316,9 +505,217 @@ pub struct PropertyValue {
 	pub property_type: String,
 
 	#[cfg_attr(feature = "serde", serde(rename = "$val"))]
+	#[cfg_attr(feature = "specta", specta(type = AnyValue))]
 	pub property_value: Value
 }
 
+impl PropertyValue {
+	/// Gets a copy of the property value with its numbers in a canonical form, so that logically equal values compare
+	/// and hash equally.
+	///
+	/// Negative zero becomes zero and floats with no fractional part become integers. JSON values can't be NaN, so
+	/// `Eq` and `Hash` are sound once numbers are canonicalised.
+	pub fn normalized(&self) -> Self {
+		Self {
+			property_type: self.property_type.to_owned(),
+			property_value: normalise_value(&self.property_value)
+		}
+	}
+
+	/// Checks that the JSON value has the shape expected for the property type. Types which aren't known to this crate,
+	/// such as enums, are accepted as-is.
+	#[try_fn]
+	pub fn validate(&self) -> Result<(), PropertyValueError> {
+		let property_type = self.property_type.as_str();
+
+		let expected = if property_type.starts_with("TArray<") {
+			"array"
+		} else {
+			match property_type {
+				"void" => "null",
+				"bool" => "boolean",
+				"int8" | "int16" | "int32" | "int64" | "uint8" | "uint16" | "uint32" | "uint64" => "integer",
+				"float32" | "float64" => "number",
+				"ZString" => "string",
+				"SEntityTemplateReference" | "ZCurve" | "ZVariant" | "SVector2" | "SVector3" | "SVector4"
+				| "SMatrix43" | "SColorRGB" | "SColorRGBA" => "object",
+				_ => return Ok(())
+			}
+		};
+
+		let valid = match expected {
+			"null" => self.property_value.is_null(),
+			"boolean" => self.property_value.is_boolean(),
+			"integer" => self.property_value.is_i64() || self.property_value.is_u64(),
+			"number" => self.property_value.is_number(),
+			"string" => self.property_value.is_string(),
+			"array" => self.property_value.is_array(),
+			_ => self.property_value.is_object()
+		};
+
+		if !valid {
+			return Err(PropertyValueError::Mismatch(self.property_type.to_owned(), expected.into()));
+		}
+	}
+
+	/// A `float32` value.
+	pub fn float32(value: f32) -> Self {
+		Self {
+			property_type: "float32".into(),
+			property_value: Value::from(value)
+		}
+	}
+
+	/// An `int32` value.
+	pub fn int32(value: i32) -> Self {
+		Self {
+			property_type: "int32".into(),
+			property_value: Value::from(value)
+		}
+	}
+
+	/// A `ZString` value.
+	pub fn string(value: impl Into<String>) -> Self {
+		Self {
+			property_type: "ZString".into(),
+			property_value: Value::String(value.into())
+		}
+	}
+
+	/// A `bool` value.
+	pub fn bool(value: bool) -> Self {
+		Self {
+			property_type: "bool".into(),
+			property_value: Value::Bool(value)
+		}
+	}
+
+	/// An `SEntityTemplateReference` value.
+	pub fn zref(value: EntityReference) -> Self {
+		Self {
+			property_type: "SEntityTemplateReference".into(),
+			property_value: serde_json::json!({
+				"entityID": value.entity_id,
+				"externalSceneIndex": value.external_scene_index,
+				"entityIndex": value.entity_index,
+				"exposedEntity": value.exposed_entity
+			})
+		}
+	}
+
+	/// Remaps the local references in an `SEntityTemplateReference` or `TArray<SEntityTemplateReference>` value. Local
+	/// references to sub-entities which no longer exist become null, or are removed from arrays.
+	fn remap_local_references(&mut self, remap: &dyn Fn(usize) -> Option<usize>) {
+		match self.property_type.as_str() {
+			"SEntityTemplateReference" => {
+				if !remap_reference_value(&mut self.property_value, remap) {
+					self.property_value = Self::zref(EntityReference::null()).property_value;
+				}
+			}
+
+			"TArray<SEntityTemplateReference>" => {
+				if let Value::Array(values) = &mut self.property_value {
+					values.retain_mut(|x| remap_reference_value(x, remap));
+				}
+			}
+
+			_ => {}
+		}
+	}
+}
+
+/// Remaps the index of a JSON `SEntityTemplateReference` if it's a local reference, returning `false` if the
+/// sub-entity it refers to no longer exists.
+fn remap_reference_value(value: &mut Value, remap: &dyn Fn(usize) -> Option<usize>) -> bool {
+	if value.get("externalSceneIndex").and_then(Value::as_i64) != Some(-1) {
+		return true;
+	}
+
+	let Some(index) = value.get("entityIndex").and_then(Value::as_u64) else {
+		return true;
+	};
+
+	match remap(index as usize) {
+		Some(index) => {
+			value["entityIndex"] = Value::from(index);
+			true
+		}
+
+		None => false
+	}
+}
+
+/// Remaps a sub-entity index, returning `false` if the sub-entity no longer exists.
+fn remap_index(index: &mut usize, remap: &dyn Fn(usize) -> Option<usize>) -> bool {
+	match remap(*index) {
+		Some(new_index) => {
+			*index = new_index;
+			true
+		}
+
+		None => false
+	}
+}
+
+/// Maps sub-entity indices from before the removal of the sub-entity at the given index to after it.
+fn index_after_removal(removed: usize) -> impl Fn(usize) -> Option<usize> {
+	move |index| match index.cmp(&removed) {
+		Ordering::Less => Some(index),
+		Ordering::Equal => None,
+		Ordering::Greater => Some(index - 1)
+	}
+}
+
+/// Maps sub-entity indices from before the insertion of a sub-entity at the given index to after it.
+fn index_after_insertion(inserted: usize) -> impl Fn(usize) -> Option<usize> {
+	move |index| Some(if index >= inserted { index + 1 } else { index })
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+pub enum PropertyValueError {
+	#[error("value of {0} property should be {1}")]
+	Mismatch(String, String)
+}
+
+fn normalise_value(value: &Value) -> Value {
+	match value {
+		Value::Number(number) => match number.as_f64() {
+			Some(float)
+				if !number.is_i64() && !number.is_u64() && float.fract() == 0.0 && float.abs() < 2f64.powi(53) =>
+			{
+				Value::from(float as i64)
+			}
+
+			_ => value.to_owned()
+		},
+
+		Value::Array(values) => Value::Array(values.iter().map(normalise_value).collect()),
+
+		Value::Object(map) => Value::Object(
+			map.iter()
+				.map(|(key, value)| (key.to_owned(), normalise_value(value)))
+				.collect()
+		),
+
+		_ => value.to_owned()
+	}
+}
+
+/// Stands in for an arbitrary JSON value in generated bindings.
+#[cfg(feature = "specta")]
+struct AnyValue;
+
+#[cfg(feature = "specta")]
+impl specta::Type for AnyValue {
+	fn inline(_: &mut specta::TypeMap, _: &[specta::DataType]) -> specta::DataType {
+		specta::DataType::Any
+	}
+}
+
 #[cfg(feature = "rune")]
 impl PropertyValue {
 	fn rune_construct(property_type: String, property_value: rune::Value) -> Self {
@@ -345,6 +742,7 @@ impl PropertyValue {
 	}
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -352,6 +750,7 @@ impl PropertyValue {
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
+#[cfg_attr(feature = "rune", rune_functions(Self::as_crc32__meta, Self::matches__meta))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", serde(untagged))]
 pub enum PropertyID {
@@ -359,6 +758,27 @@ pub enum PropertyID {
 	String(String)
 }
 
+impl PropertyID {
+	/// Gets the numeric form of the property ID, which is the CRC32 of the property name.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn as_crc32(&self) -> u64 {
+		match self {
+			PropertyID::Int(id) => *id,
+			PropertyID::String(name) => u64::from(crc32fast::hash(name.as_bytes()))
+		}
+	}
+
+	/// Whether this property ID refers to the property with the given name, in either form.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn matches(&self, name: &str) -> bool {
+		match self {
+			PropertyID::Int(id) => *id == u64::from(crc32fast::hash(name.as_bytes())),
+			PropertyID::String(property_name) => property_name == name
+		}
+	}
+}
+
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -375,6 +795,7 @@ pub struct FactorySubEntityLegacy {
 	pub post_init_property_values: Vec<Property>
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -404,6 +825,7 @@ impl EntityFactoryLegacy {
 	}
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -443,6 +865,7 @@ impl BlueprintSubEntityLegacy {
 	}
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -474,6 +897,7 @@ impl EntityBlueprintLegacy {
 	}
 }
 
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
@@ -509,7 +933,8 @@ impl EntityFactoryLegacy {
 					logical_parent: x.logical_parent,
 					platform_specific_property_values: Vec::with_capacity(0),
 					property_values: x.property_values,
-					post_init_property_values: x.post_init_property_values
+					post_init_property_values: x.post_init_property_values,
+					extra: Default::default()
 				})
 				.collect(),
 			property_overrides: self.property_overrides,
@@ -539,6 +964,116 @@ impl EntityFactory {
 			external_scene_type_indices_in_resource_header: self.external_scene_type_indices_in_resource_header
 		}
 	}
+
+	/// Gets the sub-entity a reference points to, if it's a local reference.
+	pub fn resolve(&self, reference: &EntityReference) -> Option<&FactorySubEntity> {
+		if !reference.is_local() {
+			return None;
+		}
+
+		self.sub_entities.get(reference.entity_index as usize)
+	}
+
+	/// Gets the sub-entity with the given entity ID, along with its counterpart in the factory's blueprint.
+	pub fn sub_entity_by_id<'a>(
+		&'a self,
+		blueprint: &'a EntityBlueprint,
+		entity_id: u64
+	) -> Option<(&'a FactorySubEntity, &'a BlueprintSubEntity)> {
+		let index = blueprint.index_of_entity_id(entity_id)?;
+
+		Some((self.sub_entities.get(index)?, &blueprint.sub_entities[index]))
+	}
+
+	/// Groups the property overrides by the entity they apply to, keeping the order of properties within each group.
+	pub fn grouped_overrides(&self) -> HashMap<EntityReference, Vec<Property>> {
+		let mut grouped: HashMap<EntityReference, Vec<Property>> = HashMap::new();
+
+		for property_override in &self.property_overrides {
+			grouped
+				.entry(property_override.property_owner.clone())
+				.or_default()
+				.push(property_override.property_value.clone());
+		}
+
+		grouped
+	}
+
+	/// Replaces the property overrides with the given properties, grouped by the entity they apply to.
+	pub fn set_grouped_overrides(&mut self, grouped: impl IntoIterator<Item = (EntityReference, Vec<Property>)>) {
+		self.property_overrides = grouped
+			.into_iter()
+			.flat_map(|(property_owner, properties)| {
+				properties.into_iter().map(move |property_value| PropertyOverride {
+					property_owner: property_owner.clone(),
+					property_value
+				})
+			})
+			.collect();
+	}
+
+	/// Removes the sub-entity at the given index, updating the indices of later sub-entities wherever they're referred
+	/// to. Property overrides of the removed sub-entity are removed, and references to it in logical parents and
+	/// `SEntityTemplateReference` properties become null.
+	///
+	/// The blueprint's sub-entity must be removed separately with `EntityBlueprint::remove_sub_entity`. If the root
+	/// entity is removed, `root_entity_index` is left for the caller to set.
+	#[try_fn]
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn remove_sub_entity(&mut self, index: usize) -> Result<FactorySubEntity, SubEntityIndexError> {
+		if index >= self.sub_entities.len() {
+			return Err(SubEntityIndexError::OutOfBounds(index, self.sub_entities.len()));
+		}
+
+		let removed = self.sub_entities.remove(index);
+		self.remap_sub_entity_indices(&index_after_removal(index));
+		removed
+	}
+
+	/// Inserts a sub-entity at the given index, updating the indices of the sub-entities after it wherever they're
+	/// referred to. Indices within the inserted sub-entity are left as they are, so they should already account for
+	/// the insertion.
+	///
+	/// The corresponding sub-entity must be inserted separately with `EntityBlueprint::insert_sub_entity`.
+	#[try_fn]
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn insert_sub_entity(&mut self, index: usize, sub_entity: FactorySubEntity) -> Result<(), SubEntityIndexError> {
+		if index > self.sub_entities.len() {
+			return Err(SubEntityIndexError::OutOfBounds(index, self.sub_entities.len()));
+		}
+
+		self.remap_sub_entity_indices(&index_after_insertion(index));
+		self.sub_entities.insert(index, sub_entity);
+	}
+
+	fn remap_sub_entity_indices(&mut self, remap: &dyn Fn(usize) -> Option<usize>) {
+		remap_index(&mut self.root_entity_index, remap);
+
+		for sub_entity in &mut self.sub_entities {
+			if !sub_entity.logical_parent.remap_local(remap) {
+				sub_entity.logical_parent = EntityReference::null();
+			}
+
+			for property in sub_entity
+				.property_values
+				.iter_mut()
+				.chain(sub_entity.post_init_property_values.iter_mut())
+				.chain(
+					sub_entity
+						.platform_specific_property_values
+						.iter_mut()
+						.map(|x| &mut x.property_value)
+				)
+			{
+				property.value.remap_local_references(remap);
+			}
+		}
+
+		self.property_overrides.retain_mut(|x| {
+			x.property_value.value.remap_local_references(remap);
+			x.property_owner.remap_local(remap)
+		});
+	}
 }
 
 impl EntityBlueprintLegacy {
@@ -567,7 +1102,8 @@ impl EntityBlueprintLegacy {
 						.collect(),
 					exposed_interfaces: x.exposed_interfaces,
 					logical_parent: x.logical_parent,
-					property_aliases: x.property_aliases
+					property_aliases: x.property_aliases,
+					extra: Default::default()
 				})
 				.collect(),
 			external_scene_type_indices_in_resource_header: self.external_scene_type_indices_in_resource_header,
@@ -679,4 +1215,450 @@ impl EntityBlueprint {
 			override_deletes: self.override_deletes
 		}
 	}
+
+	/// Converts to the legacy format like `into_legacy`, also returning the `editor_only` flags which the legacy format
+	/// can't represent.
+	pub fn into_legacy_with_sidecar(self) -> (EntityBlueprintLegacy, EditorOnlySidecar) {
+		let sidecar = EditorOnlySidecar {
+			editor_only_indices: self
+				.sub_entities
+				.iter()
+				.enumerate()
+				.filter(|(_, x)| x.editor_only)
+				.map(|(index, _)| index)
+				.collect()
+		};
+
+		(self.into_legacy(), sidecar)
+	}
+
+	/// Converts from the legacy format like `into_modern`, restoring the `editor_only` flags from a sidecar produced by
+	/// `into_legacy_with_sidecar`. Indices with no corresponding sub-entity are ignored.
+	pub fn from_legacy_with_sidecar(legacy: EntityBlueprintLegacy, sidecar: &EditorOnlySidecar) -> Self {
+		let mut blueprint = legacy.into_modern();
+
+		for &index in &sidecar.editor_only_indices {
+			if let Some(sub_entity) = blueprint.sub_entities.get_mut(index) {
+				sub_entity.editor_only = true;
+			}
+		}
+
+		blueprint
+	}
+
+	/// Gets the index of the sub-entity with the given entity ID.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn index_of_entity_id(&self, entity_id: u64) -> Option<usize> {
+		self.sub_entities.iter().position(|x| x.entity_id == entity_id)
+	}
+
+	/// Gets the sub-entity with the given entity ID.
+	pub fn sub_entity_by_id(&self, entity_id: u64) -> Option<&BlueprintSubEntity> {
+		self.sub_entities.iter().find(|x| x.entity_id == entity_id)
+	}
+
+	/// Removes the sub-entity at the given index, updating the indices of later sub-entities wherever they're referred
+	/// to. Pin connections, forwardings, property aliases, exposed entities, exposed interfaces and subset entries
+	/// which refer to the removed sub-entity are removed, and logical parents referring to it become null.
+	///
+	/// The factory's sub-entity must be removed separately with `EntityFactory::remove_sub_entity`. If the root entity
+	/// is removed, `root_entity_index` is left for the caller to set.
+	#[try_fn]
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn remove_sub_entity(&mut self, index: usize) -> Result<BlueprintSubEntity, SubEntityIndexError> {
+		if index >= self.sub_entities.len() {
+			return Err(SubEntityIndexError::OutOfBounds(index, self.sub_entities.len()));
+		}
+
+		let removed = self.sub_entities.remove(index);
+		self.remap_sub_entity_indices(&index_after_removal(index));
+		removed
+	}
+
+	/// Inserts a sub-entity at the given index, updating the indices of the sub-entities after it wherever they're
+	/// referred to. Indices within the inserted sub-entity are left as they are, so they should already account for
+	/// the insertion.
+	///
+	/// The corresponding sub-entity must be inserted separately with `EntityFactory::insert_sub_entity`.
+	#[try_fn]
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn insert_sub_entity(
+		&mut self,
+		index: usize,
+		sub_entity: BlueprintSubEntity
+	) -> Result<(), SubEntityIndexError> {
+		if index > self.sub_entities.len() {
+			return Err(SubEntityIndexError::OutOfBounds(index, self.sub_entities.len()));
+		}
+
+		self.remap_sub_entity_indices(&index_after_insertion(index));
+		self.sub_entities.insert(index, sub_entity);
+	}
+
+	fn remap_sub_entity_indices(&mut self, remap: &dyn Fn(usize) -> Option<usize>) {
+		remap_index(&mut self.root_entity_index, remap);
+
+		for sub_entity in &mut self.sub_entities {
+			if !sub_entity.logical_parent.remap_local(remap) {
+				sub_entity.logical_parent = EntityReference::null();
+			}
+
+			sub_entity
+				.property_aliases
+				.retain_mut(|x| remap_index(&mut x.entity_id, remap));
+
+			for exposed_entity in &mut sub_entity.exposed_entities {
+				exposed_entity.a_targets.retain_mut(|x| x.remap_local(remap));
+			}
+
+			sub_entity
+				.exposed_interfaces
+				.retain_mut(|(_, x)| remap_index(x, remap));
+
+			for (_, subset) in &mut sub_entity.entity_subsets {
+				subset.entities.retain_mut(|x| remap_index(x, remap));
+			}
+		}
+
+		self.pin_connections.retain_mut(|x| x.remap(remap));
+		self.input_pin_forwardings.retain_mut(|x| x.remap(remap));
+		self.output_pin_forwardings.retain_mut(|x| x.remap(remap));
+
+		self.override_deletes.retain_mut(|x| x.remap_local(remap));
+
+		self.pin_connection_overrides
+			.retain_mut(|x| x.from_entity.remap_local(remap) && x.to_entity.remap_local(remap));
+
+		self.pin_connection_override_deletes
+			.retain_mut(|x| x.from_entity.remap_local(remap) && x.to_entity.remap_local(remap));
+	}
+}
+
+/// The data lost when converting an `EntityBlueprint` to the legacy format, so that it can be restored afterwards.
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "rune", serde_with::apply(_ => #[rune(get, set)]))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct EditorOnlySidecar {
+	/// The indices of the sub-entities which are editor-only.
+	pub editor_only_indices: Vec<usize>
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+pub enum EntityPairError {
+	#[error("factory has {0} sub-entities but blueprint has {1}")]
+	SubEntityCountMismatch(usize, usize),
+
+	#[error("factory has root entity index {0} but blueprint has {1}")]
+	RootEntityMismatch(usize, usize),
+
+	#[error("factory has {0} external scenes but blueprint has {1}")]
+	ExternalSceneCountMismatch(usize, usize)
+}
+
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+pub enum SubEntityIndexError {
+	#[error("sub-entity index {0} is out of bounds for {1} sub-entities")]
+	OutOfBounds(usize, usize)
+}
+
+#[try_fn]
+fn check_entity_pair(factory: (usize, usize, usize), blueprint: (usize, usize, usize)) -> Result<(), EntityPairError> {
+	let (factory_sub_entities, factory_root, factory_external_scenes) = factory;
+	let (blueprint_sub_entities, blueprint_root, blueprint_external_scenes) = blueprint;
+
+	if factory_sub_entities != blueprint_sub_entities {
+		return Err(EntityPairError::SubEntityCountMismatch(
+			factory_sub_entities,
+			blueprint_sub_entities
+		));
+	}
+
+	if factory_root != blueprint_root {
+		return Err(EntityPairError::RootEntityMismatch(factory_root, blueprint_root));
+	}
+
+	if factory_external_scenes != blueprint_external_scenes {
+		return Err(EntityPairError::ExternalSceneCountMismatch(
+			factory_external_scenes,
+			blueprint_external_scenes
+		));
+	}
+}
+
+/// Converts a factory and its blueprint (a TEMP/TBLU pair) to the legacy format together, checking that they agree on
+/// their sub-entities, root entity and external scenes.
+#[try_fn]
+pub fn convert_entity_pair_to_legacy(
+	factory: EntityFactory,
+	blueprint: EntityBlueprint
+) -> Result<(EntityFactoryLegacy, EntityBlueprintLegacy), EntityPairError> {
+	check_entity_pair(
+		(
+			factory.sub_entities.len(),
+			factory.root_entity_index,
+			factory.external_scene_type_indices_in_resource_header.len()
+		),
+		(
+			blueprint.sub_entities.len(),
+			blueprint.root_entity_index,
+			blueprint.external_scene_type_indices_in_resource_header.len()
+		)
+	)?;
+
+	(factory.into_legacy(), blueprint.into_legacy())
+}
+
+/// Converts a legacy factory and its blueprint (a TEMP/TBLU pair) to the modern format together, checking that they
+/// agree on their sub-entities, root entity and external scenes.
+#[try_fn]
+pub fn convert_entity_pair_to_modern(
+	factory: EntityFactoryLegacy,
+	blueprint: EntityBlueprintLegacy
+) -> Result<(EntityFactory, EntityBlueprint), EntityPairError> {
+	check_entity_pair(
+		(
+			factory.entity_templates.len(),
+			factory.root_entity_index,
+			factory.external_scene_type_indices_in_resource_header.len()
+		),
+		(
+			blueprint.entity_templates.len(),
+			blueprint.root_entity_index,
+			blueprint.external_scene_type_indices_in_resource_header.len()
+		)
+	)?;
+
+	(factory.into_modern(), blueprint.into_modern())
+}
+
+/// The ResourceLib schema of an entity (TEMP/TBLU) JSON.
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::resourcelib))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[cfg_attr(feature = "rune", rune(constructor))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityFormat {
+	/// The format used by HITMAN 2016 and HITMAN 2, with `entityTemplates`.
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Legacy,
+
+	/// The format used by HITMAN 3, with `subEntities`.
+	#[cfg_attr(feature = "rune", rune(constructor))]
+	Modern
+}
+
+/// Detects whether an entity JSON uses the legacy or modern ResourceLib schema, so that it can be deserialised into
+/// the right type.
+#[try_fn]
+pub fn detect_entity_format(json: &str) -> Result<EntityFormat, serde_json::Error> {
+	#[derive(Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	struct FormatMarkers {
+		sub_entities: Option<serde::de::IgnoredAny>,
+		entity_templates: Option<serde::de::IgnoredAny>
+	}
+
+	let markers: FormatMarkers = serde_json::from_str(json)?;
+
+	if markers.sub_entities.is_some() {
+		EntityFormat::Modern
+	} else if markers.entity_templates.is_some() {
+		EntityFormat::Legacy
+	} else {
+		return Err(serde::de::Error::custom("expected an entity with either subEntities or entityTemplates"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn pin(from_id: usize, to_id: usize) -> PinConnection {
+		PinConnection {
+			from_id,
+			to_id,
+			..Default::default()
+		}
+	}
+
+	fn property(name: &str, value: PropertyValue) -> Property {
+		Property {
+			n_property_id: PropertyID::String(name.into()),
+			value
+		}
+	}
+
+	#[test]
+	fn remove_sub_entity_remaps_blueprint() {
+		let mut blueprint = EntityBlueprint {
+			root_entity_index: 3,
+			sub_entities: (0..4)
+				.map(|entity_id| BlueprintSubEntity {
+					entity_id,
+					..Default::default()
+				})
+				.collect(),
+			pin_connections: vec![pin(0, 2), pin(1, 3), pin(2, 3)],
+			..Default::default()
+		};
+
+		blueprint.sub_entities[2].logical_parent = EntityReference::local(1);
+		blueprint.sub_entities[3].logical_parent = EntityReference::local(2);
+		blueprint.sub_entities[0].entity_subsets = vec![(
+			"subset".into(),
+			EntitySubset {
+				entities: vec![1, 2, 3]
+			}
+		)];
+
+		let removed = blueprint.remove_sub_entity(1).unwrap();
+
+		assert_eq!(removed.entity_id, 1);
+		assert_eq!(blueprint.root_entity_index, 2);
+		assert_eq!(blueprint.pin_connections, vec![pin(0, 1), pin(1, 2)]);
+		assert!(blueprint.sub_entities[1].logical_parent.is_null());
+		assert_eq!(blueprint.sub_entities[2].logical_parent, EntityReference::local(1));
+		assert_eq!(blueprint.sub_entities[0].entity_subsets[0].1.entities, vec![1, 2]);
+	}
+
+	#[test]
+	fn remove_sub_entity_remaps_factory() {
+		let mut factory = EntityFactory {
+			sub_entities: vec![FactorySubEntity::default(); 4],
+			property_overrides: vec![
+				PropertyOverride {
+					property_owner: EntityReference::local(1),
+					property_value: property("m_bVisible", PropertyValue::bool(false))
+				},
+				PropertyOverride {
+					property_owner: EntityReference::local(3),
+					property_value: property("m_bVisible", PropertyValue::bool(true))
+				}
+			],
+			..Default::default()
+		};
+
+		factory.sub_entities[0].property_values = vec![
+			property("m_target", PropertyValue::zref(EntityReference::local(1))),
+			property("m_other", PropertyValue::zref(EntityReference::local(2)))
+		];
+
+		factory.remove_sub_entity(1).unwrap();
+
+		assert_eq!(factory.sub_entities.len(), 3);
+		assert_eq!(factory.property_overrides.len(), 1);
+		assert_eq!(factory.property_overrides[0].property_owner, EntityReference::local(2));
+		assert_eq!(
+			factory.sub_entities[0].property_values[0].value,
+			PropertyValue::zref(EntityReference::null())
+		);
+		assert_eq!(
+			factory.sub_entities[0].property_values[1].value,
+			PropertyValue::zref(EntityReference::local(1))
+		);
+	}
+
+	#[test]
+	fn remove_sub_entity_out_of_bounds() {
+		let mut blueprint = EntityBlueprint {
+			sub_entities: vec![BlueprintSubEntity::default()],
+			..Default::default()
+		};
+
+		assert!(matches!(
+			blueprint.remove_sub_entity(1),
+			Err(SubEntityIndexError::OutOfBounds(1, 1))
+		));
+		assert_eq!(blueprint.sub_entities.len(), 1);
+	}
+
+	#[test]
+	fn insert_sub_entity_shifts_blueprint() {
+		let mut blueprint = EntityBlueprint {
+			root_entity_index: 1,
+			sub_entities: vec![BlueprintSubEntity::default(); 2],
+			pin_connections: vec![pin(0, 1)],
+			output_pin_forwardings: vec![pin(1, 0)],
+			..Default::default()
+		};
+
+		blueprint.sub_entities[1].logical_parent = EntityReference::local(0);
+		blueprint.sub_entities[1].entity_subsets = vec![(
+			"subset".into(),
+			EntitySubset {
+				entities: vec![0, 1]
+			}
+		)];
+
+		let inserted = BlueprintSubEntity {
+			entity_id: 5,
+			logical_parent: EntityReference::local(2),
+			..Default::default()
+		};
+
+		blueprint.insert_sub_entity(1, inserted).unwrap();
+
+		assert_eq!(blueprint.root_entity_index, 2);
+		assert_eq!(blueprint.pin_connections, vec![pin(0, 2)]);
+		assert_eq!(blueprint.output_pin_forwardings, vec![pin(2, 0)]);
+		assert_eq!(blueprint.sub_entities[1].entity_id, 5);
+		assert_eq!(blueprint.sub_entities[1].logical_parent, EntityReference::local(2));
+		assert_eq!(blueprint.sub_entities[2].logical_parent, EntityReference::local(0));
+		assert_eq!(blueprint.sub_entities[2].entity_subsets[0].1.entities, vec![0, 2]);
+	}
+
+	#[test]
+	fn insert_sub_entity_shifts_factory() {
+		let mut factory = EntityFactory {
+			sub_entities: vec![FactorySubEntity::default(); 2],
+			property_overrides: vec![PropertyOverride {
+				property_owner: EntityReference::local(1),
+				property_value: property("m_bVisible", PropertyValue::bool(false))
+			}],
+			..Default::default()
+		};
+
+		factory.sub_entities[0].property_values = vec![property(
+			"m_target",
+			PropertyValue::zref(EntityReference::local(1))
+		)];
+
+		factory.insert_sub_entity(0, FactorySubEntity::default()).unwrap();
+
+		assert_eq!(factory.sub_entities.len(), 3);
+		assert_eq!(factory.property_overrides[0].property_owner, EntityReference::local(2));
+		assert_eq!(
+			factory.sub_entities[1].property_values[0].value,
+			PropertyValue::zref(EntityReference::local(2))
+		);
+	}
+
+	#[test]
+	fn insert_sub_entity_out_of_bounds() {
+		let mut factory = EntityFactory::default();
+
+		assert!(matches!(
+			factory.insert_sub_entity(1, FactorySubEntity::default()),
+			Err(SubEntityIndexError::OutOfBounds(1, 0))
+		));
+		assert!(factory.sub_entities.is_empty());
+	}
 }
@@ -1,4 +1,4 @@
-use std::io::{Cursor, Read};
+use std::{io::{Cursor, Read}, str::FromStr};
 
 #[cfg(feature = "hash_list")]
 use hashbrown::HashMap;
@@ -9,7 +9,16 @@ use tryvial::try_fn;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::metadata::{ExtendedResourceMetadata, FromStrError, RuntimeID};
+use crate::{
+	game::GameVersion,
+	metadata::{
+		known_compressed_types, ExtendedResourceMetadata, FromStrError, MemoryRequirement, MetadataCalculationError,
+		ReferenceFlags, ResourceMetadata, ResourceType, ResourceTypeError, RuntimeID
+	}
+};
+
+#[cfg(feature = "serde")]
+use crate::metadata::serialize_display;
 
 #[cfg(feature = "hash_list")]
 use crate::hash_list::HashData;
@@ -22,6 +31,7 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 	module.ty::<RpkgResourceMeta>()?;
 	module.ty::<RpkgResourceReference>()?;
 	module.ty::<RpkgInteropError>()?;
+	module.ty::<Inconsistency>()?;
 
 	module
 }
@@ -31,7 +41,17 @@ pub fn rune_module() -> Result<rune::Module, rune::ContextError> {
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::rpkg_tool))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
-#[cfg_attr(feature = "rune", rune_functions(Self::r_new))]
+#[cfg_attr(
+	feature = "rune",
+	rune_functions(
+		Self::r_new,
+		Self::verify_table_size__meta,
+		Self::with_final_size__meta,
+		Self::with_compressed_size__meta,
+		Self::with_sizes__meta,
+		Self::without_path__meta
+	)
+)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct RpkgResourceMeta {
 	pub hash_offset: u64,
@@ -45,6 +65,7 @@ pub struct RpkgResourceMeta {
 	pub hash_size_in_video_memory: u32,
 	pub hash_value: String,
 
+	/// The path of the resource, if known. This is informational only and is not part of the binary format.
 	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
 	pub hash_path: Option<String>
 }
@@ -76,85 +97,439 @@ pub struct RpkgResourceReference {
 type Result<T, E = RpkgInteropError> = std::result::Result<T, E>;
 
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "data"))]
 #[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
 #[cfg_attr(feature = "rune", rune(item = ::hitman_commons::rpkg_tool))]
 #[cfg_attr(feature = "rune", rune_derive(STRING_DISPLAY, STRING_DEBUG))]
 #[cfg_attr(feature = "rune", rune(constructor))]
 pub enum RpkgInteropError {
-	#[error("seek error: {0}")]
-	Seek(#[from] std::io::Error),
+	#[error("IO error while {0}: {1}")]
+	Io(String, #[source] #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))] std::io::Error),
 
 	#[error("invalid number: {0}")]
-	InvalidNumber(#[from] std::num::TryFromIntError),
+	InvalidNumber(
+		#[from]
+		#[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))]
+		std::num::TryFromIntError
+	),
 
 	#[error("invalid hex value: {0}")]
-	InvalidHex(#[from] std::num::ParseIntError),
+	InvalidHex(
+		#[from]
+		#[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))]
+		std::num::ParseIntError
+	),
 
 	#[error("invalid ResourceID: {0}")]
-	InvalidResourceID(#[from] FromStrError)
+	InvalidResourceID(#[from] #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))] FromStrError),
+
+	#[error("invalid resource type: {0}")]
+	InvalidResourceType(
+		#[from]
+		#[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))]
+		ResourceTypeError
+	),
+
+	#[error("declared reference count {0} does not fit in the remaining {1} bytes")]
+	ReferenceCountTooLarge(u32, u64),
+
+	#[cfg(feature = "serde")]
+	#[error("JSON error: {0}")]
+	Json(#[from] #[serde(serialize_with = "serialize_display")] serde_json::Error)
+}
+
+/// A way in which a meta disagrees with its resource's data.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "data"))]
+#[cfg_attr(feature = "rune", derive(better_rune_derive::Any))]
+#[cfg_attr(feature = "rune", rune(item = ::hitman_commons::rpkg_tool))]
+#[cfg_attr(feature = "rune", rune_derive(STRING_DEBUG))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Inconsistency {
+	/// The resource type isn't known to this crate, so nothing else could be checked.
+	UnknownResourceType(String),
+
+	/// Whether the resource is stored as compressed, and whether resources of its type usually are.
+	Compression(bool, bool),
+
+	/// The stored final size, and the actual size of the data.
+	FinalSize(u32, u64),
+
+	/// The stored system memory requirement, and the one calculated from the data.
+	SystemMemoryRequirement(u32, u32),
+
+	/// The stored video memory requirement, and the one calculated from the data.
+	VideoMemoryRequirement(u32, u32)
+}
+
+/// The format of a `.meta` file.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetaFormat {
+	/// The binary format used by RPKG Tool's `.meta` files.
+	Binary,
+
+	/// The JSON format used by RPKG Tool's `.meta.json` files.
+	Json
+}
+
+#[cfg(feature = "serde")]
+impl RpkgResourceMeta {
+	/// Reads a `.meta` file in the given format.
+	#[try_fn]
+	pub fn read(mut reader: impl Read, format: MetaFormat) -> Result<Self> {
+		match format {
+			MetaFormat::Binary => {
+				let mut content = vec![];
+				reader
+					.read_to_end(&mut content)
+					.map_err(|x| RpkgInteropError::Io("reading the meta file".into(), x))?;
+				Self::from_binary(&content)?
+			}
+
+			MetaFormat::Json => serde_json::from_reader(reader)?
+		}
+	}
+
+	/// Writes a `.meta` file in the given format.
+	#[try_fn]
+	pub fn write(&self, mut writer: impl std::io::Write, format: MetaFormat) -> Result<()> {
+		match format {
+			MetaFormat::Binary => writer
+				.write_all(&self.to_binary()?)
+				.map_err(|x| RpkgInteropError::Io("writing the meta file".into(), x))?,
+			MetaFormat::Json => serde_json::to_writer(writer, self)?
+		}
+	}
+
+	/// Parses a `.meta.json` file, accepting the schemas of older RPKG Tool versions as well as the current one.
+	///
+	/// Field names are matched regardless of casing, and references may be given as an array of objects, an array of
+	/// hashes (which are given the default flag) or an object mapping hashes to flags.
+	#[try_fn]
+	pub fn from_json_any(json: &str) -> Result<Self> {
+		serde_json::from_value(normalise_meta_json(serde_json::from_str(json)?))?
+	}
+
+	/// Parses a `.meta.json` file, rejecting any fields which aren't part of the current RPKG Tool schema.
+	#[try_fn]
+	pub fn from_json_strict(json: &str) -> Result<Self> {
+		serde_json::from_str::<StrictRpkgResourceMeta>(json)?.into()
+	}
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrictRpkgResourceMeta {
+	hash_offset: u64,
+	hash_reference_data: Vec<StrictRpkgResourceReference>,
+	hash_reference_table_dummy: u32,
+	hash_reference_table_size: u32,
+	hash_resource_type: String,
+	hash_size: u32,
+	hash_size_final: u32,
+	hash_size_in_memory: u32,
+	hash_size_in_video_memory: u32,
+	hash_value: String,
+
+	#[serde(default)]
+	hash_path: Option<String>
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrictRpkgResourceReference {
+	hash: String,
+	flag: String
+}
+
+#[cfg(feature = "serde")]
+impl From<StrictRpkgResourceMeta> for RpkgResourceMeta {
+	fn from(meta: StrictRpkgResourceMeta) -> Self {
+		Self {
+			hash_offset: meta.hash_offset,
+			hash_reference_data: meta
+				.hash_reference_data
+				.into_iter()
+				.map(|x| RpkgResourceReference {
+					hash: x.hash,
+					flag: x.flag
+				})
+				.collect(),
+			hash_reference_table_dummy: meta.hash_reference_table_dummy,
+			hash_reference_table_size: meta.hash_reference_table_size,
+			hash_resource_type: meta.hash_resource_type,
+			hash_size: meta.hash_size,
+			hash_size_final: meta.hash_size_final,
+			hash_size_in_memory: meta.hash_size_in_memory,
+			hash_size_in_video_memory: meta.hash_size_in_video_memory,
+			hash_value: meta.hash_value,
+			hash_path: meta.hash_path
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+fn normalise_meta_json(value: serde_json::Value) -> serde_json::Value {
+	use serde_json::{Map, Value};
+
+	let normalise_object = |object: Map<String, Value>| {
+		object
+			.into_iter()
+			.map(|(key, value)| (normalise_meta_key(&key), value))
+			.collect::<Map<_, _>>()
+	};
+
+	match value {
+		Value::Object(object) => Value::Object(
+			normalise_object(object)
+				.into_iter()
+				.map(|(key, value)| {
+					let value = if key == "hash_reference_data" {
+						match value {
+							Value::Array(references) => Value::Array(
+								references
+									.into_iter()
+									.map(|reference| match reference {
+										Value::String(hash) => serde_json::json!({ "hash": hash, "flag": "1F" }),
+										Value::Object(reference) => Value::Object(normalise_object(reference)),
+										x => x
+									})
+									.collect()
+							),
+
+							Value::Object(references) => Value::Array(
+								references
+									.into_iter()
+									.map(|(hash, flag)| serde_json::json!({ "hash": hash, "flag": flag }))
+									.collect()
+							),
+
+							x => x
+						}
+					} else {
+						value
+					};
+
+					(key, value)
+				})
+				.collect()
+		),
+
+		x => x
+	}
+}
+
+/// Converts a camelCase or PascalCase key to snake_case.
+#[cfg(feature = "serde")]
+fn normalise_meta_key(key: &str) -> String {
+	let mut normalised = String::with_capacity(key.len() + 4);
+	let mut previous_lowercase = false;
+
+	for char in key.chars() {
+		if char.is_ascii_uppercase() && previous_lowercase {
+			normalised.push('_');
+		}
+
+		previous_lowercase = char.is_ascii_lowercase() || char.is_ascii_digit();
+		normalised.push(char.to_ascii_lowercase());
+	}
+
+	normalised
+}
+
+/// Fills the buffer from the cursor, reporting the offset the read started at if it fails.
+fn read_exact_at(cursor: &mut Cursor<&[u8]>, buf: &mut [u8]) -> Result<()> {
+	let offset = cursor.position();
+	cursor
+		.read_exact(buf)
+		.map_err(|x| RpkgInteropError::Io(format!("reading at offset {offset:#X}"), x))
 }
 
 impl RpkgResourceMeta {
+	/// Checks the meta against the resource's (decompressed) data: the compression flag against what's usual for the
+	/// resource type, the final size against the size of the data and the memory requirements against those calculated
+	/// from the data, where this crate can calculate them exactly. Estimated requirements, such as the video memory
+	/// requirements of textures, aren't compared.
+	///
+	/// Some types are only usually compressed, so a compression inconsistency doesn't always mean the meta is wrong.
+	/// Types whose compression isn't catalogued aren't checked for it.
+	#[try_fn]
+	pub fn validate_against(
+		&self,
+		data: &[u8],
+		game_version: GameVersion
+	) -> Result<Vec<Inconsistency>, MetadataCalculationError> {
+		let resource_type = match ResourceType::try_from(self.hash_resource_type.as_str()) {
+			Ok(resource_type) if resource_type.is_known() => resource_type,
+			_ => return Ok(vec![Inconsistency::UnknownResourceType(self.hash_resource_type.to_owned())])
+		};
+
+		let mut inconsistencies = vec![];
+
+		// Types whose compression isn't catalogued can't be checked
+		if known_compressed_types().contains(&resource_type) {
+			let compressed = self.hash_size & 0x7FFFFFFF != 0;
+			let usually_compressed = ResourceMetadata::infer_compressed_for(resource_type, game_version);
+
+			if compressed != usually_compressed {
+				inconsistencies.push(Inconsistency::Compression(compressed, usually_compressed));
+			}
+		}
+
+		if u64::from(self.hash_size_final) != data.len() as u64 {
+			inconsistencies.push(Inconsistency::FinalSize(self.hash_size_final, data.len() as u64));
+		}
+
+		match ResourceMetadata::system_memory_requirement(resource_type, data) {
+			Ok(MemoryRequirement::Exact(system_memory_requirement)) => {
+				if self.hash_size_in_memory != system_memory_requirement {
+					inconsistencies.push(Inconsistency::SystemMemoryRequirement(
+						self.hash_size_in_memory,
+						system_memory_requirement
+					));
+				}
+			}
+
+			// Nothing to compare against
+			Ok(MemoryRequirement::Estimated(_)) | Err(MetadataCalculationError::Uncatalogued(_)) => {}
+
+			Err(x) => return Err(x)
+		}
+
+		match ResourceMetadata::video_memory_requirement(resource_type, data) {
+			Ok(MemoryRequirement::Exact(video_memory_requirement)) => {
+				if self.hash_size_in_video_memory != video_memory_requirement {
+					inconsistencies.push(Inconsistency::VideoMemoryRequirement(
+						self.hash_size_in_video_memory,
+						video_memory_requirement
+					));
+				}
+			}
+
+			// Estimates aren't compared, so failing to make one (such as for an unsupported texture format) doesn't
+			// matter either
+			Ok(MemoryRequirement::Estimated(_)) | Err(_) => {}
+		}
+
+		inconsistencies
+	}
+
+	/// Computes the size of the reference table for a resource with the given number of references.
+	///
+	/// The table consists of the reference count followed by one flag byte and one 8-byte ID per reference, and is
+	/// omitted entirely if there are no references. Fails if the size doesn't fit in the meta's 32-bit field.
+	pub fn reference_table_size(reference_count: usize) -> Result<u32, std::num::TryFromIntError> {
+		match reference_count {
+			0 => Ok(0x0),
+			n => u32::try_from(0x4 + (u64::from(u32::try_from(n)?) * 0x9))
+		}
+	}
+
+	/// Whether the stored `hash_reference_table_size` agrees with the size computed from the references.
+	///
+	/// Conversions from rpkg-rs take the size from the resource as-is, which may not match if it includes padding.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn verify_table_size(&self) -> bool {
+		Self::reference_table_size(self.hash_reference_data.len()).is_ok_and(|x| x == self.hash_reference_table_size)
+	}
+
 	#[try_fn]
 	pub fn from_binary(content: &[u8]) -> Result<Self> {
+		Self::read_binary(&mut Cursor::new(content))?
+	}
+
+	/// Parses a stream of concatenated `.meta` records until the content is exhausted.
+	#[try_fn]
+	pub fn from_binary_many(content: &[u8]) -> Result<Vec<Self>> {
 		let mut cursor = Cursor::new(content);
+		let mut metas = vec![];
+
+		while (cursor.position() as usize) < content.len() {
+			let start = cursor.position();
+			let meta = Self::read_binary(&mut cursor)?;
+
+			// The reference table may be padded beyond the references that were read
+			cursor.set_position(start + 0x2C + u64::from(meta.hash_reference_table_size));
+
+			metas.push(meta);
+		}
+
+		metas
+	}
+
+	#[try_fn]
+	fn read_binary(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+		let content = *cursor.get_ref();
 
 		let mut hash_value = [0; 8];
-		cursor.read_exact(&mut hash_value)?;
+		read_exact_at(cursor, &mut hash_value)?;
 		let hash_value = format!("{:0>16X}", u64::from_le_bytes(hash_value));
 
 		let mut hash_offset = [0; 8];
-		cursor.read_exact(&mut hash_offset)?;
+		read_exact_at(cursor, &mut hash_offset)?;
 		let hash_offset = u64::from_le_bytes(hash_offset);
 
 		let mut hash_size = [0; 4];
-		cursor.read_exact(&mut hash_size)?;
+		read_exact_at(cursor, &mut hash_size)?;
 		let hash_size = u32::from_le_bytes(hash_size);
 
 		let mut hash_resource_type = [0; 4];
-		cursor.read_exact(&mut hash_resource_type)?;
-		let hash_resource_type = String::from_utf8_lossy(&hash_resource_type).to_string();
+		read_exact_at(cursor, &mut hash_resource_type)?;
+		let hash_resource_type = String::from(ResourceType::try_from(hash_resource_type)?);
 
 		let mut hash_reference_table_size = [0; 4];
-		cursor.read_exact(&mut hash_reference_table_size)?;
+		read_exact_at(cursor, &mut hash_reference_table_size)?;
 		let hash_reference_table_size = u32::from_le_bytes(hash_reference_table_size);
 
 		let mut hash_reference_table_dummy = [0; 4];
-		cursor.read_exact(&mut hash_reference_table_dummy)?;
+		read_exact_at(cursor, &mut hash_reference_table_dummy)?;
 		let hash_reference_table_dummy = u32::from_le_bytes(hash_reference_table_dummy);
 
 		let mut hash_size_final = [0; 4];
-		cursor.read_exact(&mut hash_size_final)?;
+		read_exact_at(cursor, &mut hash_size_final)?;
 		let hash_size_final = u32::from_le_bytes(hash_size_final);
 
 		let mut hash_size_in_memory = [0; 4];
-		cursor.read_exact(&mut hash_size_in_memory)?;
+		read_exact_at(cursor, &mut hash_size_in_memory)?;
 		let hash_size_in_memory = u32::from_le_bytes(hash_size_in_memory);
 
 		let mut hash_size_in_video_memory = [0; 4];
-		cursor.read_exact(&mut hash_size_in_video_memory)?;
+		read_exact_at(cursor, &mut hash_size_in_video_memory)?;
 		let hash_size_in_video_memory = u32::from_le_bytes(hash_size_in_video_memory);
 
 		let mut dependencies: Vec<RpkgResourceReference> = vec![];
 
 		if hash_reference_table_size != 0 {
 			let mut hash_reference_count = [0; 4];
-			cursor.read_exact(&mut hash_reference_count)?;
+			read_exact_at(cursor, &mut hash_reference_count)?;
 			let hash_reference_count = u32::from_le_bytes(hash_reference_count);
 			let hash_reference_count = hash_reference_count & 0x3FFFFFFF;
 
-			let mut flags = vec![];
-			let mut references = vec![];
+			let remaining = content.len() as u64 - cursor.position().min(content.len() as u64);
+
+			if u64::from(hash_reference_count) * 9 > remaining {
+				return Err(RpkgInteropError::ReferenceCountTooLarge(
+					hash_reference_count,
+					remaining
+				));
+			}
+
+			let mut flags = Vec::with_capacity(hash_reference_count as usize);
+			let mut references = Vec::with_capacity(hash_reference_count as usize);
 
 			for _ in 0..hash_reference_count {
 				let mut flag = [0; 1];
-				cursor.read_exact(&mut flag)?;
+				read_exact_at(cursor, &mut flag)?;
 				flags.push(flag[0]);
 			}
 
 			for _ in 0..hash_reference_count {
 				let mut reference = [0; 8];
-				cursor.read_exact(&mut reference)?;
+				read_exact_at(cursor, &mut reference)?;
 				references.push(u64::from_le_bytes(reference));
 			}
 
@@ -194,17 +569,25 @@ impl RpkgResourceMeta {
 		data.extend(self.hash_size.to_le_bytes());
 		data.extend(self.hash_resource_type.as_bytes());
 
-		data.extend(if self.hash_reference_data.is_empty() {
-			[0; 4]
-		} else {
-			u32::try_from(self.hash_reference_data.len() * 9 + 4)?.to_le_bytes()
-		}); // Recalculate hash_reference_table_size
+		// Recalculate hash_reference_table_size
+		data.extend(Self::reference_table_size(self.hash_reference_data.len())?.to_le_bytes());
 
 		data.extend(self.hash_reference_table_dummy.to_le_bytes());
 		data.extend(self.hash_size_final.to_le_bytes());
 		data.extend(self.hash_size_in_memory.to_le_bytes());
 		data.extend(self.hash_size_in_video_memory.to_le_bytes());
 
+		data.extend(self.reference_chunk_bytes()?);
+
+		data
+	}
+
+	/// Serialises the reference table alone, as it appears after the header in `to_binary`: the reference count, then
+	/// the flags of each reference, then their IDs. This is empty if there are no references.
+	#[try_fn]
+	pub fn reference_chunk_bytes(&self) -> Result<Vec<u8>> {
+		let mut data = Vec::with_capacity(Self::reference_table_size(self.hash_reference_data.len())? as usize);
+
 		if !self.hash_reference_data.is_empty() {
 			data.extend((u32::try_from(self.hash_reference_data.len())? | 0xC0000000).to_le_bytes());
 
@@ -220,7 +603,14 @@ impl RpkgResourceMeta {
 		data
 	}
 
-	pub fn from_resource_metadata(metadata: ExtendedResourceMetadata, use_legacy_flags: bool) -> Self {
+	/// Creates a meta from resource metadata.
+	///
+	/// The metadata doesn't include the resource's sizes, so `hash_size_final` (and the size part of `hash_size`, if
+	/// the resource is compressed) are set to placeholder values; use `with_sizes` to set the real sizes.
+	///
+	/// Fails if the resource has too many references for the size of the reference table to fit in the meta.
+	#[try_fn]
+	pub fn from_resource_metadata(metadata: ExtendedResourceMetadata, use_legacy_flags: bool) -> Result<Self> {
 		RpkgResourceMeta {
 			hash_offset: 0,
 			hash_size: if metadata.core_info.compressed { 1000 } else { 0 }
@@ -247,14 +637,39 @@ impl RpkgResourceMeta {
 					hash: reference.resource.to_string()
 				})
 				.collect(),
-			hash_reference_table_size: match &metadata.core_info.references.len() {
-				0 => 0x0,
-				n => 0x4 + (*n as u32 * 0x9)
-			},
+			hash_reference_table_size: Self::reference_table_size(metadata.core_info.references.len())?,
 			hash_reference_table_dummy: 0
 		}
 	}
 
+	/// Clears the path, so that metas which differ only in their (informational) path compare equal.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn without_path(mut self) -> Self {
+		self.hash_path = None;
+		self
+	}
+
+	/// Sets the uncompressed size of the resource.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn with_final_size(mut self, final_size: u32) -> Self {
+		self.hash_size_final = final_size;
+		self
+	}
+
+	/// Sets the compressed size of the resource, keeping the scrambled flag. This should be 0 if the resource is not
+	/// compressed.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn with_compressed_size(mut self, compressed_size: u32) -> Self {
+		self.hash_size = (self.hash_size & 0x80000000) | (compressed_size & 0x7FFFFFFF);
+		self
+	}
+
+	/// Sets both the compressed and uncompressed sizes of the resource.
+	#[cfg_attr(feature = "rune", rune::function(keep))]
+	pub fn with_sizes(self, compressed_size: u32, final_size: u32) -> Self {
+		self.with_compressed_size(compressed_size).with_final_size(final_size)
+	}
+
 	#[cfg(feature = "hash_list")]
 	#[try_fn]
 	pub fn apply_hash_list(&mut self, hash_list: &HashMap<RuntimeID, HashData>) -> Result<(), RpkgInteropError> {
@@ -297,6 +712,21 @@ impl RpkgResourceMeta {
 		self.normalise_hashes()?;
 		self
 	}
+
+	/// Parses the references into their IDs and flags. Reference hashes must be hex IDs; use `normalise_hashes` first
+	/// if any are paths.
+	#[try_fn]
+	pub fn parsed_references(&self) -> Result<Vec<(RuntimeID, ReferenceFlags)>, RpkgInteropError> {
+		self.hash_reference_data
+			.iter()
+			.map(|reference| {
+				Ok((
+					RuntimeID::from_str(&reference.hash)?,
+					ReferenceFlags::from_any(u8::from_str_radix(&reference.flag, 16)?)
+				))
+			})
+			.collect::<Result<_>>()?
+	}
 }
 
 #[cfg(feature = "rpkg-rs")]
@@ -365,3 +795,71 @@ impl From<&ResourceInfo> for RpkgResourceMeta {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn texture_meta(resource_type: &str, data: &[u8], video_memory_requirement: u32) -> RpkgResourceMeta {
+		RpkgResourceMeta {
+			hash_value: "00F0E4A4D6A8B4C2".into(),
+			hash_resource_type: resource_type.into(),
+			hash_size: 0x6A1F,
+			hash_size_final: data.len() as u32,
+			hash_size_in_memory: 0xFFFFFFFF,
+			hash_size_in_video_memory: video_memory_requirement,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn estimated_video_memory_is_not_an_inconsistency() {
+		// A 256x256 DXT5 texture with a full mip chain; the game's metadata includes padding the estimate doesn't
+		let mut text = vec![0; 0x100];
+		text[0xC..0xE].copy_from_slice(&256u16.to_le_bytes());
+		text[0xE..0x10].copy_from_slice(&256u16.to_le_bytes());
+		text[0x10..0x12].copy_from_slice(&0x4Fu16.to_le_bytes());
+		text[0x12] = 9;
+
+		let texd = vec![0; 0x15570];
+
+		for (resource_type, data) in [("TEXT", &text), ("TEXD", &texd)] {
+			let inconsistencies = texture_meta(resource_type, data, 0x15800)
+				.validate_against(data, GameVersion::H3)
+				.unwrap();
+
+			assert!(
+				!inconsistencies
+					.iter()
+					.any(|x| matches!(x, Inconsistency::VideoMemoryRequirement(..)))
+			);
+		}
+	}
+
+	#[test]
+	fn unsupported_texture_format_still_validates() {
+		let mut text = vec![0; 0x100];
+		text[0x10..0x12].copy_from_slice(&0xFFFFu16.to_le_bytes());
+
+		let inconsistencies = texture_meta("TEXT", &text, 0x15800)
+			.with_final_size(0x200)
+			.validate_against(&text, GameVersion::H3)
+			.unwrap();
+
+		assert!(inconsistencies.contains(&Inconsistency::FinalSize(0x200, 0x100)));
+	}
+
+	#[test]
+	fn uncatalogued_compression_is_not_checked() {
+		let meta = RpkgResourceMeta {
+			hash_resource_type: "BLOB".into(),
+			hash_size: 0,
+			hash_size_final: 4,
+			hash_size_in_memory: 0xFFFFFFFF,
+			hash_size_in_video_memory: 0xFFFFFFFF,
+			..Default::default()
+		};
+
+		assert_eq!(meta.validate_against(&[0; 4], GameVersion::H3).unwrap(), vec![]);
+	}
+}